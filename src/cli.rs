@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use clap_complete::Shell;
 use url::Url;
 
@@ -12,6 +12,19 @@ pub struct Settings {
     #[clap(short, long)]
     /// Verbose mode
     verbose: bool,
+
+    #[clap(long, value_enum, global = true, default_value_t = ErrorFormat::Human)]
+    /// The format of the error output
+    error_format: ErrorFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+/// The format of the error output
+pub enum ErrorFormat {
+    /// A human readable message
+    Human,
+    /// A JSON object with the `kind`, `code` and `message` of the error
+    Json,
 }
 
 impl Settings {
@@ -21,6 +34,12 @@ impl Settings {
         self.verbose
     }
 
+    /// The format of the error output
+    #[must_use]
+    pub const fn error_format(&self) -> ErrorFormat {
+        self.error_format
+    }
+
     /// Get the command
     #[must_use]
     pub const fn command(&self) -> &Command {
@@ -39,6 +58,10 @@ pub enum Command {
         default: bool,
     },
 
+    /// Manage the configuration
+    #[clap(subcommand)]
+    Config(ConfigOperation),
+
     /// Interactively commit using the prompts
     Commit {
         #[clap(long)]
@@ -48,23 +71,93 @@ pub enum Command {
         #[clap(long)]
         /// Add the `--amend` flag for git commit command
         amend: bool,
+
+        #[clap(long, conflicts_with = "print")]
+        /// Print the message and the git command without committing
+        dry_run: bool,
+
+        #[clap(long)]
+        /// Print the message on stdout without committing, e.g. for `git commit -F -`
+        print: bool,
+
+        #[clap(long)]
+        /// Reuse the message saved when the last commit failed
+        resume: bool,
+
+        #[clap(last = true, allow_hyphen_values = true)]
+        /// Extra arguments for the git commit command, like `-- --no-verify`
+        git_args: Vec<String>,
     },
 
     /// Sync emoji list with the repository
     Update {
-        /// Change the update URL
+        /// Change the update URL, used without configured sources
         url: Option<Url>,
+
+        #[clap(long, conflicts_with = "url")]
+        /// Only update the catalog from this source
+        source: Option<String>,
+
+        #[clap(long)]
+        /// Print the changes without writing them, fail when an update is available
+        check: bool,
     },
 
     /// List all available gitmojis
     List,
 
+    /// Check the environment: git, the configuration, the catalog, the hooks, and the terminal
+    Doctor {
+        #[clap(long)]
+        /// Skip the network check of the catalog sources
+        offline: bool,
+    },
+
     /// Search gitmojis
     Search {
         /// Search text
         text: String,
     },
 
+    /// Validate the commit messages of a range, like `origin/main..HEAD`
+    Validate {
+        /// The commit range
+        range: String,
+
+        #[clap(long)]
+        /// The branch used by the rules, default to the current branch
+        branch: Option<String>,
+    },
+
+    /// Create a fixup, squash, or amend commit for a recent commit
+    Fixup {
+        #[clap(long, value_enum, default_value_t = FixupKind::Fixup)]
+        /// The kind of commit, applied by `git rebase --autosquash`
+        kind: FixupKind,
+
+        #[clap(long, default_value_t = 20)]
+        /// The number of recent commits to pick from
+        count: usize,
+
+        #[clap(long)]
+        /// Add the `--all` flag for git commit command
+        all: bool,
+
+        #[clap(long)]
+        /// Run `git rebase --autosquash` after the commit
+        autosquash: bool,
+
+        #[clap(last = true, allow_hyphen_values = true)]
+        /// Extra arguments for the git commit command, like `-- --no-verify`
+        git_args: Vec<String>,
+    },
+
+    /// Reword a commit of the current branch with the prompts
+    Reword {
+        /// The commit to reword, like `HEAD~2`
+        rev: String,
+    },
+
     /// Create or remove git commit hook
     #[cfg(feature = "hook")]
     #[clap(subcommand)]
@@ -77,20 +170,148 @@ pub enum Command {
     },
 }
 
+#[derive(Debug, Clone, Subcommand)]
+/// Available configuration operation
+pub enum ConfigOperation {
+    /// Print a preference, by default from the merged configuration
+    Get {
+        /// The preference key, like `format`
+        key: String,
+
+        #[clap(flatten)]
+        /// The configuration file
+        target: ConfigTarget,
+    },
+    /// Set a preference, by default in the user configuration
+    Set {
+        /// The preference key, like `format`
+        key: String,
+
+        /// The value, like `UseEmoji`
+        value: String,
+
+        #[clap(flatten)]
+        /// The configuration file
+        target: ConfigTarget,
+    },
+    /// Remove a preference, by default from the user configuration
+    Unset {
+        /// The preference key, like `format`
+        key: String,
+
+        #[clap(flatten)]
+        /// The configuration file
+        target: ConfigTarget,
+    },
+    /// List the preferences, by default from the merged configuration
+    List {
+        #[clap(flatten)]
+        /// The configuration file
+        target: ConfigTarget,
+    },
+    /// Open the configuration file in the git editor, by default the user configuration
+    Edit {
+        #[clap(flatten)]
+        /// The configuration file
+        target: ConfigTarget,
+    },
+    /// Check the configuration files, and the catalog
+    Validate,
+    /// Print the JSON schema, by default of the user configuration
+    Schema {
+        #[clap(flatten)]
+        /// The configuration file
+        target: ConfigTarget,
+    },
+    /// Migrate the configuration file to the latest version
+    Migrate {
+        #[clap(long)]
+        /// Print the migration steps without writing them
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, Args)]
+/// The configuration file to use
+pub struct ConfigTarget {
+    #[clap(long, conflicts_with = "global")]
+    /// Use the repository configuration, `.gitmojis.toml` or the `gitmoji.file` git config
+    local: bool,
+
+    #[clap(long)]
+    /// Use the user configuration
+    global: bool,
+}
+
+impl ConfigTarget {
+    /// If the repository configuration is used
+    #[must_use]
+    pub const fn local(&self) -> bool {
+        self.local
+    }
+
+    /// If a configuration file is explicitly targeted
+    #[must_use]
+    pub const fn is_explicit(&self) -> bool {
+        self.local || self.global
+    }
+}
+
 #[cfg(feature = "hook")]
 #[derive(Debug, Clone, Subcommand)]
 /// Available hook operation
 pub enum HookOperation {
     /// Add the hook
-    Add,
-    /// Remove the hook
+    Add {
+        #[clap(long)]
+        /// Keep an existing hook, and call it before gitmoji
+        chain: bool,
+    },
+    /// Remove the hook, and restore the previous one
     Remove,
+    /// Show the hook installation status
+    Status,
+    /// Print the configuration for a hook manager
+    Snippet {
+        /// The hook manager
+        #[clap(value_enum)]
+        manager: HookManager,
+    },
     /// (Used by the hook to create commit message)
     Apply {
         /// The commit message file
         dest: std::path::PathBuf,
 
         /// The commit source
+        #[clap(env = "PRE_COMMIT_COMMIT_MSG_SOURCE")]
         source: Option<String>,
     },
+    /// (Used by the hook to validate the commit message)
+    Validate {
+        /// The commit message file
+        dest: std::path::PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+/// The kind of fixup commit
+pub enum FixupKind {
+    /// A `fixup!` commit, keeping the message of the target
+    Fixup,
+    /// A `squash!` commit, adding a message to the target
+    Squash,
+    /// An `amend!` commit, replacing the message of the target
+    Amend,
+}
+
+#[cfg(feature = "hook")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+/// Supported hook managers
+pub enum HookManager {
+    /// <https://pre-commit.com/>
+    PreCommit,
+    /// <https://typicode.github.io/husky/>
+    Husky,
+    /// <https://github.com/evilmartians/lefthook>
+    Lefthook,
 }
@@ -1,8 +1,7 @@
-use console::Term;
-use dialoguer::theme::ColorfulTheme;
-use dialoguer::{FuzzySelect, Input};
-
-use crate::{ConventionalEmojiCommit, Gitmoji, GitmojiConfig, Result};
+use crate::{
+    CommitMessage, ConventionalEmojiCommit, Gitmoji, GitmojiConfig, Prompter, Result,
+    SCOPE_SEPARATOR,
+};
 
 pub struct DefaultCommitParams {
     pub gitmoji: Gitmoji,
@@ -19,48 +18,166 @@ pub struct ConventionalEmojiCommitParams {
     pub description: Option<String>,
 }
 
-pub fn get_default_commit_params(
+/// Pick a gitmoji, the `previous` gitmoji (emoji or code) is selected by default
+pub fn pick_gitmoji(
     config: &GitmojiConfig,
-    term: &Term,
-) -> Result<DefaultCommitParams> {
-    let theme = ColorfulTheme::default();
-
-    let gitmoji_idx = FuzzySelect::with_theme(&theme)
-        .with_prompt("Pick your flavor")
-        .items(config.gitmojis())
-        .default(0)
-        .interact_on(term)?;
+    previous: Option<&str>,
+    prompter: &dyn Prompter,
+) -> Result<Gitmoji> {
+    let items = config
+        .gitmojis()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let default = previous
+        .and_then(|previous| {
+            config
+                .gitmojis()
+                .iter()
+                .position(|it| it.emoji() == previous || it.code() == previous)
+        })
+        .unwrap_or_default();
+    let gitmoji_idx = prompter.fuzzy_select("Pick your flavor", &items, default)?;
 
     let gitmoji = config
         .gitmojis()
+        .get(gitmoji_idx)
+        .expect("Should be in bounds")
+        .clone();
+    Ok(gitmoji)
+}
+
+/// Pick a conventional emoji, the `previous` emoji and type are selected by default
+pub fn pick_conventional_emoji(
+    config: &GitmojiConfig,
+    previous: Option<(&str, Option<&str>)>,
+    prompter: &dyn Prompter,
+) -> Result<ConventionalEmojiCommit> {
+    let items = config
+        .conventional_commit_emojis()
         .iter()
-        .nth(gitmoji_idx)
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let default = previous
+        .and_then(|(previous, r#type)| {
+            let emojis = config.conventional_commit_emojis();
+            let same_emoji =
+                |it: &&ConventionalEmojiCommit| it.emoji() == previous || it.code() == previous;
+            emojis
+                .iter()
+                .position(|it| same_emoji(&it) && Some(it.r#type()) == r#type)
+                .or_else(|| emojis.iter().position(|it| same_emoji(&it)))
+        })
+        .unwrap_or_default();
+    let emoji_idx = prompter.fuzzy_select("Pick your flavor", &items, default)?;
+
+    let emoji = config
+        .conventional_commit_emojis()
+        .get(emoji_idx)
         .expect("Should be in bounds")
         .clone();
-    let scope = if config.scope() {
-        // TODO: [#2] add an history
-        let scope = Input::with_theme(&theme)
-            .with_prompt("Enter the scope of current changes:")
-            .default("*".to_string())
-            .interact_text_on(term)?;
-        Some(scope)
-    } else {
+    Ok(emoji)
+}
+
+/// Pick the detected scopes, the user can still edit the result
+fn pick_detected_scope(scopes: &[String], prompter: &dyn Prompter) -> Result<String> {
+    if let [scope] = scopes {
+        return Ok(scope.clone());
+    }
+
+    let mut items = vec![scopes.join(SCOPE_SEPARATOR)];
+    items.extend(scopes.iter().cloned());
+    let idx = prompter.select("Pick the detected scopes", &items, 0)?;
+
+    Ok(items.swap_remove(idx))
+}
+
+/// Pick one of the allowed scopes, no scope, or enter another one when allowed
+fn pick_allowed_scope(
+    config: &GitmojiConfig,
+    scopes: &[String],
+    prompter: &dyn Prompter,
+) -> Result<Option<String>> {
+    let allowed = config.scopes();
+    let mut items = allowed.iter().map(ToString::to_string).collect::<Vec<_>>();
+    items.push(String::from("No scope"));
+    if config.allow_other_scope() {
+        items.push(String::from("Other scope…"));
+    }
+    let default = scopes
+        .iter()
+        .find_map(|scope| allowed.iter().position(|it| it.name() == scope))
+        .unwrap_or_default();
+    let idx = prompter.fuzzy_select("Pick the scope of current changes", &items, default)?;
+
+    if let Some(scope) = allowed.get(idx) {
+        return Ok(Some(scope.name().to_string()));
+    }
+    if idx == allowed.len() {
+        return Ok(None);
+    }
+    let default = if scopes.is_empty() {
         None
+    } else {
+        Some(pick_detected_scope(scopes, prompter)?)
+    };
+    let scope = prompter.input(
+        "Enter the scope of current changes:",
+        default.as_deref(),
+        false,
+    )?;
+    Ok(Some(scope))
+}
+
+fn ask_title_description(
+    previous: Option<&CommitMessage>,
+    prompter: &dyn Prompter,
+) -> Result<(String, Option<String>)> {
+    let title = prompter.input(
+        "Enter the commit title",
+        previous.map(CommitMessage::title),
+        false,
+    )?;
+    // A single line input would join the lines of the body
+    let body = previous.and_then(CommitMessage::body);
+    let description = if body.is_some_and(|body| body.contains('\n')) {
+        prompter.edit("Edit the commit message", body)?
+    } else {
+        prompter.input("Enter the commit message:", body, true)?
     };
-    let title = Input::with_theme(&theme)
-        .with_prompt("Enter the commit title")
-        .allow_empty(false)
-        .interact_text_on(term)?;
-    let description: String = Input::with_theme(&theme)
-        .with_prompt("Enter the commit message:")
-        .allow_empty(true)
-        .interact_text_on(term)?;
     let description = if description.is_empty() {
         None
     } else {
         Some(description)
     };
 
+    Ok((title, description))
+}
+
+/// Ask the commit parameters, prefilled with the `previous` message when rewording
+pub fn get_default_commit_params(
+    config: &GitmojiConfig,
+    scopes: &[String],
+    previous: Option<&CommitMessage>,
+    prompter: &dyn Prompter,
+) -> Result<DefaultCommitParams> {
+    let gitmoji = pick_gitmoji(config, previous.map(CommitMessage::gitmoji), prompter)?;
+    let scope = if config.scope() && !config.scopes().is_empty() {
+        pick_allowed_scope(config, scopes, prompter)?
+    } else if config.scope() {
+        // TODO: [#2] add an history
+        let default = if scopes.is_empty() {
+            "*".to_string()
+        } else {
+            pick_detected_scope(scopes, prompter)?
+        };
+        let scope = prompter.input("Enter the scope of current changes:", Some(&default), false)?;
+        Some(scope)
+    } else {
+        previous.and_then(CommitMessage::scope).map(str::to_string)
+    };
+    let (title, description) = ask_title_description(previous, prompter)?;
+
     let result = DefaultCommitParams {
         gitmoji,
         scope,
@@ -70,55 +187,189 @@ pub fn get_default_commit_params(
     Ok(result)
 }
 
+/// Ask the commit parameters, prefilled with the `previous` message when rewording
 pub fn get_conventional_emoji_commit_params(
     config: &GitmojiConfig,
-    term: &Term,
+    scopes: &[String],
+    previous: Option<&CommitMessage>,
+    prompter: &dyn Prompter,
 ) -> Result<ConventionalEmojiCommitParams> {
-    let theme = ColorfulTheme::default();
-
-    let gitmoji_idx = FuzzySelect::with_theme(&theme)
-        .with_prompt("Pick your flavor")
-        .items(config.conventional_commit_emojis())
-        .default(0)
-        .interact_on(term)?;
-
-    let emoji = config
-        .conventional_commit_emojis()
-        .iter()
-        .nth(gitmoji_idx)
-        .expect("Should be in bounds")
-        .clone();
+    let emoji = pick_conventional_emoji(
+        config,
+        previous.map(|previous| (previous.gitmoji(), previous.r#type())),
+        prompter,
+    )?;
     let type_name = emoji.clone().r#type().to_string();
-    let scope = if config.scope() {
-        let scope = Input::with_theme(&theme)
-            .with_prompt("Enter the scope of current changes:")
-            .allow_empty(true)
-            .interact_text_on(term)?;
+    let scope = if config.scope() && !config.scopes().is_empty() {
+        pick_allowed_scope(config, scopes, prompter)?
+    } else if config.scope() {
+        let default = if scopes.is_empty() {
+            None
+        } else {
+            Some(pick_detected_scope(scopes, prompter)?)
+        };
+        let scope = prompter.input(
+            "Enter the scope of current changes:",
+            default.as_deref(),
+            true,
+        )?;
         Some(scope)
     } else {
-        None
-    };
-
-    let title = Input::with_theme(&theme)
-        .with_prompt("Enter the commit title")
-        .allow_empty(false)
-        .interact_text_on(term)?;
-    let description: String = Input::with_theme(&theme)
-        .with_prompt("Enter the commit message:")
-        .allow_empty(true)
-        .interact_text_on(term)?;
-    let description = if description.is_empty() {
-        None
-    } else {
-        Some(description)
+        previous.and_then(CommitMessage::scope).map(str::to_string)
     };
+    let (title, description) = ask_title_description(previous, prompter)?;
 
     let result = ConventionalEmojiCommitParams {
         emoji,
+        type_name,
         scope,
         title,
         description,
-        type_name,
     };
     Ok(result)
 }
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+    use crate::{
+        Answer, CommitSpecification, EmojiFormat, LocalGitmojiConfig, ScriptedPrompter, DEFAULT_URL,
+    };
+
+    fn config(scope: bool) -> GitmojiConfig {
+        let mut config = GitmojiConfig::new(
+            false,
+            CommitSpecification::Default,
+            EmojiFormat::UseCode,
+            false,
+            scope,
+            DEFAULT_URL.parse().unwrap(),
+        );
+        config.set_gitmojis(vec![
+            Gitmoji::new(
+                String::from("🐛"),
+                String::from(":bug:"),
+                Some(String::from("bug")),
+                Some(String::from("Fix a bug.")),
+            ),
+            Gitmoji::new(
+                String::from("✨"),
+                String::from(":sparkles:"),
+                Some(String::from("sparkles")),
+                Some(String::from("Introduce new features.")),
+            ),
+        ]);
+        config
+    }
+
+    fn text(value: &str) -> Answer {
+        Answer::Text(String::from(value))
+    }
+
+    #[test]
+    fn should_ask_default_commit_params() {
+        let prompter =
+            ScriptedPrompter::new([text("sparkles"), text("Add the search"), Answer::Default]);
+
+        let result = get_default_commit_params(&config(false), &[], None, &prompter);
+
+        let_assert!(Ok(params) = result);
+        check!(params.gitmoji.code() == ":sparkles:");
+        check!(params.scope == None);
+        check!(params.title == "Add the search");
+        check!(params.description == None);
+        check!(prompter.remaining() == 0);
+    }
+
+    #[test]
+    fn should_prefill_detected_scopes() {
+        let scopes = vec![String::from("api"), String::from("web")];
+        let prompter = ScriptedPrompter::new([
+            Answer::Select(0),
+            Answer::Select(2),
+            Answer::Default,
+            text("Fix the title"),
+            text("Some details"),
+        ]);
+
+        let result = get_default_commit_params(&config(true), &scopes, None, &prompter);
+
+        let_assert!(Ok(params) = result);
+        check!(params.gitmoji.code() == ":bug:");
+        check!(params.scope == Some(String::from("web")));
+        check!(params.description == Some(String::from("Some details")));
+    }
+
+    #[test]
+    fn should_prefill_previous_message() {
+        let previous: CommitMessage = ":sparkles: (api): Add the search\n\nWith filters"
+            .parse()
+            .unwrap();
+        let scopes = vec![String::from("api")];
+        let prompter = ScriptedPrompter::new([
+            Answer::Default,
+            Answer::Default,
+            text("Add the fuzzy search"),
+            Answer::Default,
+        ]);
+
+        let result = get_default_commit_params(&config(true), &scopes, Some(&previous), &prompter);
+
+        let_assert!(Ok(params) = result);
+        check!(params.gitmoji.code() == ":sparkles:");
+        check!(params.scope == Some(String::from("api")));
+        check!(params.title == "Add the fuzzy search");
+        check!(params.description == Some(String::from("With filters")));
+    }
+
+    #[test]
+    fn should_pick_no_scope_from_allowed_scopes() {
+        let mut config = config(true);
+        let local_config: LocalGitmojiConfig = toml_edit::de::from_str(
+            r#"
+[[scopes]]
+name = "api"
+
+[[scopes]]
+name = "web"
+"#,
+        )
+        .unwrap();
+        config.merge(&local_config);
+        let prompter = ScriptedPrompter::new([
+            text("bug"),
+            Answer::Select(2),
+            text("Fix the title"),
+            Answer::Default,
+        ]);
+
+        let result = get_default_commit_params(&config, &[], None, &prompter);
+
+        let_assert!(Ok(params) = result);
+        check!(params.scope == None);
+        check!(prompter.remaining() == 0);
+    }
+
+    #[test]
+    fn should_keep_previous_scope_and_edit_body() {
+        let previous = CommitMessage::builder(":sparkles:", "Add the search")
+            .scope("api: ")
+            .body("With filters\nand sorting")
+            .build();
+        let prompter = ScriptedPrompter::new([
+            Answer::Default,
+            text("Add the fuzzy search"),
+            Answer::Default,
+        ]);
+
+        let result = get_default_commit_params(&config(false), &[], Some(&previous), &prompter);
+
+        let_assert!(Ok(params) = result);
+        check!(params.scope == Some(String::from("api: ")));
+        check!(params.description == Some(String::from("With filters\nand sorting")));
+        check!(prompter.remaining() == 0);
+    }
+}
@@ -1,21 +1,29 @@
+use std::env;
 use std::fmt::{self, Display};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
-use console::Term;
-use dialoguer::theme::ColorfulTheme;
-use dialoguer::{Confirm, Input, Select};
 use directories::ProjectDirs;
+use serde::de::DeserializeOwned;
 use tokio::fs;
+use toml_edit::Document;
 use tracing::{info, warn};
 
+use super::migrate::{migrate, split_catalog, with_version, Migration};
+use super::preference::apply_env_overrides;
 use crate::{
-    git, CommitSpecification, EmojiFormat, Error, GitmojiConfig, LocalGitmojiConfig, Result,
-    DEFAULT_URL, CONVENTIONAL_EMOJI_COMMITS_DEFAULT_URL
+    git, CommitSpecification, EmojiFormat, Error, GitmojiConfig, LocalGitmojiConfig, Prompter,
+    Result, CONVENTIONAL_EMOJI_COMMITS_DEFAULT_URL, DEFAULT_URL,
 };
 
 const CONFIG_FILE: &str = "gitmojis.toml";
+const CATALOG_FILE: &str = "catalog.toml";
 const CONFIG_LOCAL_FILE: &str = "./.gitmojis.toml";
-const GIT_CONFIG_LOCAL_FILE: &str = "gitmoji.file";
+pub(super) const GIT_CONFIG_LOCAL_FILE: &str = "gitmoji.file";
+/// The environment variable with an alternate config file
+const CONFIG_ENV_VAR: &str = "GITMOJI_CONFIG";
+/// The environment variable with an alternate catalog file
+const CATALOG_ENV_VAR: &str = "GITMOJI_CATALOG";
 const DIR_QUALIFIER: &str = "com.github";
 const DIR_ORGANIZATION: &str = "ilaborie";
 const DIR_APPLICATION: &str = "gitmoji-rs";
@@ -66,135 +74,447 @@ const FORMAT_ITEMS: &[FormatItem<'static>] = &[
     },
 ];
 
-pub fn create_config(term: &Term) -> Result<GitmojiConfig> {
-    let theme = ColorfulTheme::default();
-    let auto_add = Confirm::with_theme(&theme)
-        .with_prompt(r#"Enable automatic "git add .""#)
-        .default(false)
-        .interact_on(term)?;
-
-    let specification_idx = Select::with_theme(&theme)
-        .with_prompt("Select the commit specification")
-        .default(0)
-        .items(SPECIFICATION_ITEMS)
-        .interact_on(term)?;
+/// Create the configuration from the user answers
+///
+/// # Errors
+/// Fail if a prompt cannot be answered
+pub fn create_config(prompter: &dyn Prompter) -> Result<GitmojiConfig> {
+    let auto_add = prompter.confirm(r#"Enable automatic "git add .""#, false)?;
+
+    let items = SPECIFICATION_ITEMS
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let specification_idx = prompter.select("Select the commit specification", &items, 0)?;
     let specification = SPECIFICATION_ITEMS[specification_idx].value;
 
-    let format_idx = Select::with_theme(&theme)
-        .with_prompt("Select how emojis should be used in commits")
-        .default(0)
-        .items(FORMAT_ITEMS)
-        .interact_on(term)?;
+    let items = FORMAT_ITEMS
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let format_idx = prompter.select("Select how emojis should be used in commits", &items, 0)?;
     let format = FORMAT_ITEMS[format_idx].value;
 
-
-    let signed = Confirm::with_theme(&theme)
-        .with_prompt("Enable signed commits")
-        .default(false)
-        .interact_on(term)?;
+    let signed = prompter.confirm("Enable signed commits", false)?;
 
     let scope = match specification {
-        CommitSpecification::Default => {
-            Confirm::with_theme(&theme)
-            .with_prompt("Enable scope prompt")
-            .default(false)
-            .interact_on(term)?
-        },
-        CommitSpecification::ConventionalEmojiCommits => {true}
+        CommitSpecification::Default => prompter.confirm("Enable scope prompt", false)?,
+        CommitSpecification::ConventionalEmojiCommits => true,
     };
 
     let default_url = match specification {
         CommitSpecification::Default => DEFAULT_URL,
-        CommitSpecification::ConventionalEmojiCommits => CONVENTIONAL_EMOJI_COMMITS_DEFAULT_URL
+        CommitSpecification::ConventionalEmojiCommits => CONVENTIONAL_EMOJI_COMMITS_DEFAULT_URL,
+    };
+    let update_url = loop {
+        let url = prompter.input("Set gitmojis api url", Some(default_url), false)?;
+        match url.parse() {
+            Ok(url) => break url,
+            Err(err) => eprintln!("⚠️  Invalid URL '{url}', {err}"),
+        }
     };
-    let update_url = Input::with_theme(&theme)
-        .with_prompt("Set gitmojis api url")
-        .default(default_url.to_string())
-        .validate_with(validate_url)
-        .interact_text_on(term)?
-        .parse()?;
 
     let config = GitmojiConfig::new(auto_add, specification, format, signed, scope, update_url);
     Ok(config)
 }
 
-#[allow(clippy::ptr_arg)]
-fn validate_url(s: &String) -> Result<()> {
-    let _url = s.parse::<url::Url>()?;
-    Ok(())
+fn get_project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from(DIR_QUALIFIER, DIR_ORGANIZATION, DIR_APPLICATION)
+        .ok_or_else(|| Error::CannotGetProjectConfigFile("cannot define project dir".to_string()))
+}
+
+async fn get_file_in(dir: &Path, file: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .await
+        .map_err(|err| Error::CannotGetProjectConfigFile(err.to_string()))?;
+
+    Ok(dir.join(file))
+}
+
+// The file of the environment variable, if any
+async fn get_env_file(env_var: &str) -> Result<Option<PathBuf>> {
+    let Some(path) = env::var_os(env_var).filter(|it| !it.is_empty()) else {
+        return Ok(None);
+    };
+    let file = PathBuf::from(path);
+    if let Some(dir) = file.parent().filter(|it| !it.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .await
+            .map_err(|err| Error::CannotGetProjectConfigFile(err.to_string()))?;
+    }
+    Ok(Some(file))
 }
 
-/// Get the configuration file
+/// Get the configuration file, with the user preferences
+///
+/// The `GITMOJI_CONFIG` environment variable overrides the file in the configuration directory
 ///
 /// # Errors
 /// Fail if we cannot create the parent directory
 pub async fn get_config_file() -> Result<PathBuf> {
-    let project_dir = ProjectDirs::from(DIR_QUALIFIER, DIR_ORGANIZATION, DIR_APPLICATION)
-        .ok_or_else(|| {
-            Error::CannotGetProjectConfigFile("cannot define project dir".to_string())
-        })?;
+    if let Some(file) = get_env_file(CONFIG_ENV_VAR).await? {
+        return Ok(file);
+    }
+    let project_dirs = get_project_dirs()?;
+    get_file_in(project_dirs.config_dir(), CONFIG_FILE).await
+}
 
-    let config_dir = project_dir.config_dir();
-    fs::create_dir_all(config_dir)
-        .await
-        .map_err(|err| Error::CannotGetProjectConfigFile(err.to_string()))?;
+/// Get the catalog file, with the fetched gitmojis, in the cache directory
+///
+/// The `GITMOJI_CATALOG` environment variable overrides the file in the cache directory
+///
+/// # Errors
+/// Fail if we cannot create the parent directory
+pub async fn get_catalog_file() -> Result<PathBuf> {
+    if let Some(file) = get_env_file(CATALOG_ENV_VAR).await? {
+        return Ok(file);
+    }
+    let project_dirs = get_project_dirs()?;
+    get_file_in(project_dirs.cache_dir(), CATALOG_FILE).await
+}
 
-    let mut config_file = config_dir.to_path_buf();
-    config_file.push(CONFIG_FILE);
+// The line and the column of the byte offset, starting at 1
+fn position(contents: &str, offset: usize) -> (usize, usize) {
+    let before = contents.get(..offset).unwrap_or(contents);
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |it| it.chars().count())
+        + 1;
+    (line, column)
+}
 
-    Ok(config_file)
+fn invalid_config_file(
+    file: &Path,
+    contents: &str,
+    err: &toml_edit::de::Error,
+    span: Option<Range<usize>>,
+) -> Error {
+    let position = span.map(|span| position(contents, span.start));
+    let reason = err.message().trim().lines().collect::<Vec<_>>().join(", ");
+    Error::InvalidConfigFile {
+        file: file.to_path_buf(),
+        line: position.map(|(line, _)| line),
+        column: position.map(|(_, column)| column),
+        reason,
+    }
 }
 
-async fn read_config() -> Result<GitmojiConfig> {
+async fn read_document(file: &Path) -> Result<(String, Document)> {
+    let contents = fs::read_to_string(file).await?;
+    let document = contents
+        .parse::<Document>()
+        .map_err(|err| invalid_config_file(file, &contents, &err.clone().into(), err.span()))?;
+    Ok((contents, document))
+}
+
+// The parsed document has no span, so the contents are deserialized again to locate the error
+fn locate_error<T>(file: &Path, contents: &str, err: &toml_edit::de::Error) -> Error
+where
+    T: DeserializeOwned,
+{
+    let span = toml_edit::de::from_str::<T>(contents)
+        .err()
+        .and_then(|it| it.span());
+    invalid_config_file(file, contents, err, span)
+}
+
+fn deserialize<T>(file: &Path, contents: &str, document: Document) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    toml_edit::de::from_document(document).map_err(|err| locate_error::<T>(file, contents, &err))
+}
+
+// Write the migrated config, after a backup of the previous file
+async fn save_migration(
+    config_file: &Path,
+    catalog_file: &Path,
+    document: &Document,
+    migration: &Migration,
+) -> Result<PathBuf> {
+    let mut backup = config_file.as_os_str().to_owned();
+    backup.push(format!(".v{}.bak", migration.previous_version()));
+    let backup = PathBuf::from(backup);
+    info!("Backup config file {config_file:?} to {backup:?}");
+    fs::copy(config_file, &backup).await?;
+
+    let catalog = migration.catalog();
+    if !catalog.is_empty() {
+        info!("Migrate the catalog from {config_file:?} to {catalog_file:?}");
+        fs::write(catalog_file, catalog.to_string()).await?;
+    }
+    fs::write(config_file, document.to_string()).await?;
+
+    Ok(backup)
+}
+
+// The user configuration, without the repository configuration, nor the environment overrides
+async fn read_user_config() -> Result<GitmojiConfig> {
     let config_file = get_config_file().await?;
+    if !config_file.exists() {
+        return Err(Error::MissingConfigFile);
+    }
     info!("Read config file {config_file:?}");
-    let bytes = fs::read(config_file).await?;
-    let mut config = toml_edit::de::from_slice::<GitmojiConfig>(&bytes)?;
+    let (contents, mut document) = read_document(&config_file).await?;
+
+    // Migrated in memory, the files are only written by `gitmoji config migrate`, or an update
+    let catalog_file = get_catalog_file().await?;
+    let migration = migrate(&mut document)?;
+    if !migration.is_empty() {
+        warn!(
+            "The config file {config_file:?} uses the version {}, run `gitmoji config migrate`",
+            migration.previous_version()
+        );
+    }
+    let mut catalog = migration.catalog().clone();
+    let mut catalog_contents = None;
+    if catalog.is_empty() && catalog_file.exists() {
+        info!("Read catalog file {catalog_file:?}");
+        let (contents, document) = read_document(&catalog_file).await?;
+        catalog = document;
+        catalog_contents = Some(contents);
+    }
+    for (key, item) in catalog.iter() {
+        document.insert(key, item.clone());
+    }
+
+    toml_edit::de::from_document::<GitmojiConfig>(document).map_err(|err| {
+        // When the preferences alone are valid, the error is in the catalog
+        let in_catalog = toml_edit::de::from_str::<GitmojiConfig>(&contents).is_ok();
+        match &catalog_contents {
+            Some(catalog_contents) if in_catalog => {
+                locate_error::<GitmojiConfig>(&catalog_file, catalog_contents, &err)
+            }
+            _ => locate_error::<GitmojiConfig>(&config_file, &contents, &err),
+        }
+    })
+}
+
+async fn read_config() -> Result<GitmojiConfig> {
+    let mut config = read_user_config().await?;
     let local_config = read_local_config().await?;
     config.merge(&local_config);
 
-    Ok(config)
+    apply_env_overrides(config, |name| env::var(name).ok())
 }
 
-async fn read_local_config() -> Result<LocalGitmojiConfig> {
-    let mut path = git::get_config_value(GIT_CONFIG_LOCAL_FILE).await?;
-    if path.is_empty() {
-        path = String::from(CONFIG_LOCAL_FILE);
+/// Migrate the config file to the latest version, the previous file is kept as a backup
+///
+/// With `dry_run`, nothing is written.
+/// Return the migration, and the backup file
+///
+/// # Errors
+/// Fail when the config file is not found, or cannot be migrated
+pub async fn migrate_config_file(dry_run: bool) -> Result<(Migration, Option<PathBuf>)> {
+    let config_file = get_config_file().await?;
+    if !config_file.exists() {
+        return Err(Error::MissingConfigFile);
+    }
+    let (_, mut document) = read_document(&config_file).await?;
+    let migration = migrate(&mut document)?;
+    if dry_run || migration.is_empty() {
+        return Ok((migration, None));
     }
-    let file = Path::new(&path);
+    let catalog_file = get_catalog_file().await?;
+    let backup = save_migration(&config_file, &catalog_file, &document, &migration).await?;
+
+    Ok((migration, Some(backup)))
+}
+
+/// Get the repository configuration file, from the `gitmoji.file` git config, or `.gitmojis.toml`
+///
+/// # Errors
+/// Fail if we cannot read the git config
+pub async fn get_local_config_file() -> Result<PathBuf> {
+    let path = git::get_config_value(GIT_CONFIG_LOCAL_FILE).await?;
+    let result = if path.is_empty() {
+        PathBuf::from(CONFIG_LOCAL_FILE)
+    } else {
+        PathBuf::from(path)
+    };
+    Ok(result)
+}
+
+async fn read_local_config() -> Result<LocalGitmojiConfig> {
+    let file = get_local_config_file().await?;
     let result = if file.exists() {
         info!("Read local config file {file:?}");
-        let bytes = fs::read(file).await?;
-        toml_edit::de::from_slice(&bytes)?
+        let (contents, document) = read_document(&file).await?;
+        deserialize(&file, &contents, document)?
     } else {
-        warn!("Cannot read local config, file {path:?} does not exists");
+        warn!("Cannot read local config, file {file:?} does not exists");
         LocalGitmojiConfig::default()
     };
 
     Ok(result)
 }
+
+/// Read the document of the repository, or the user config file
+///
+/// The user config file is migrated to the latest version in memory
+///
+/// # Errors
+/// Fail when the user config file is not found, or when the file is not a valid TOML
+pub async fn read_config_document(local: bool) -> Result<(PathBuf, Document)> {
+    if local {
+        let file = get_local_config_file().await?;
+        let document = if file.exists() {
+            read_document(&file).await?.1
+        } else {
+            Document::new()
+        };
+        return Ok((file, document));
+    }
+    let file = get_config_file().await?;
+    if !file.exists() {
+        return Err(Error::MissingConfigFile);
+    }
+    let (_, mut document) = read_document(&file).await?;
+    migrate(&mut document)?;
+    Ok((file, document))
+}
+
+/// Read the document of the repository, or the user config file, to edit it
+///
+/// The user config file is migrated to the latest version before, with a backup
+///
+/// # Errors
+/// Fail when the user config file is not found, or when the file is not a valid TOML
+pub async fn edit_config_document(local: bool) -> Result<(PathBuf, Document)> {
+    if !local {
+        migrate_config_file(false).await?;
+    }
+    read_config_document(local).await
+}
+
+/// Check the repository, or the user config file, can be read
+///
+/// # Errors
+/// Fail when the file is not a valid configuration
+pub async fn check_config_file(file: &Path, local: bool) -> Result<()> {
+    let (contents, document) = read_document(file).await?;
+    if local {
+        deserialize::<LocalGitmojiConfig>(file, &contents, document)?;
+    } else {
+        migrate(&mut document.clone())?;
+        deserialize::<GitmojiConfig>(file, &contents, document)?;
+    }
+    Ok(())
+}
+
 /// Read the user config file
 ///
 /// # Errors
-/// Fail when the config file is not found
+/// Fail when the config file is not found, or is invalid
 pub async fn read_config_or_fail() -> Result<GitmojiConfig> {
-    read_config().await.map_err(|_| Error::MissingConfigFile)
+    read_config().await
 }
 
 /// Read the user config file, if the file does not exists, return the default configuration
-pub async fn read_config_or_default() -> GitmojiConfig {
-    read_config().await.unwrap_or_default()
+///
+/// The environment variables still override the default configuration
+///
+/// # Errors
+/// Fail when the config file is invalid
+pub async fn read_config_or_default() -> Result<GitmojiConfig> {
+    match read_config().await {
+        Err(Error::MissingConfigFile) => {
+            apply_env_overrides(GitmojiConfig::default(), |name| env::var(name).ok())
+        }
+        result => result,
+    }
 }
 
-/// Write config
+/// Read the user config file to write it back,
+/// without the repository configuration, nor the environment overrides
+///
+/// If the file does not exists, return the default configuration
+///
+/// # Errors
+/// Fail when the config file is invalid
+pub(super) async fn read_user_config_or_default() -> Result<GitmojiConfig> {
+    match read_user_config().await {
+        Err(Error::MissingConfigFile) => Ok(GitmojiConfig::default()),
+        result => result,
+    }
+}
+
+// The preferences, and the catalog documents of the config
+fn split_config(config: &GitmojiConfig) -> Result<(Document, Document)> {
+    let contents = toml_edit::ser::to_string_pretty(config)?;
+    let mut document = contents
+        .parse::<Document>()
+        .map_err(toml_edit::de::Error::from)?;
+    let catalog = split_catalog(&mut document);
+    Ok((with_version(&document), catalog))
+}
+
+/// Write config, the user preferences in the config directory, and the catalog in the cache directory
 ///
 /// # Errors
 /// Fail when I/O trouble to get or write the file
 /// Might fail during serialization of config
 pub async fn write_config(config: &GitmojiConfig) -> Result<()> {
+    let (document, catalog) = split_config(config)?;
+
     let config_file = get_config_file().await?;
-    let contents = toml_edit::ser::to_string_pretty(config)?;
     info!("Update config file {config_file:?}");
-    fs::write(config_file, contents).await?;
+    fs::write(config_file, document.to_string()).await?;
+
+    let catalog_file = get_catalog_file().await?;
+    info!("Update catalog file {catalog_file:?}");
+    fs::write(catalog_file, catalog.to_string()).await?;
+
+    Ok(())
+}
+
+/// Write the catalog of the config in the cache directory, the user preferences are not written
+///
+/// # Errors
+/// Fail when I/O trouble to get or write the file
+/// Might fail during serialization of config
+pub(super) async fn write_catalog(config: &GitmojiConfig) -> Result<()> {
+    let (_, catalog) = split_config(config)?;
+
+    let catalog_file = get_catalog_file().await?;
+    info!("Update catalog file {catalog_file:?}");
+    fs::write(catalog_file, catalog.to_string()).await?;
+
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+    use crate::{Answer, ScriptedPrompter};
+
+    #[test]
+    fn should_create_config_with_answers() {
+        let url = "https://example.com/gitmojis.json";
+        let prompter = ScriptedPrompter::new([
+            Answer::Confirm(true),
+            Answer::Default,
+            Answer::Select(1),
+            Answer::Default,
+            Answer::Confirm(true),
+            Answer::Text(String::from("not an url")),
+            Answer::Text(String::from(url)),
+        ]);
+
+        let result = create_config(&prompter);
+
+        let_assert!(Ok(config) = result);
+        check!(config.auto_add());
+        check!(config.specification() == CommitSpecification::Default);
+        check!(*config.format() == EmojiFormat::UseEmoji);
+        check!(!config.signed());
+        check!(config.scope());
+        check!(config.update_url() == url);
+        check!(prompter.remaining() == 0);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+
+use crate::model::is_same_gitmoji;
+use crate::{GitmojiConfig, GitmojiRule};
+
+/// An issue of a configuration that can be read, but misleads the prompts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum ConfigIssue {
+    /// Several gitmojis with the same code
+    DuplicateGitmojiCode(String),
+    /// Several conventional emoji commits with the same type
+    DuplicateConventionalType(String),
+    /// A catalog entry with a blank description
+    EmptyDescription(String),
+    /// A rule gitmoji that is not in the catalog
+    UnknownRuleGitmoji(String),
+}
+
+impl Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateGitmojiCode(code) => write!(f, "duplicate gitmoji code '{code}'"),
+            Self::DuplicateConventionalType(r#type) => {
+                write!(f, "duplicate conventional emoji commit type '{type}'")
+            }
+            Self::EmptyDescription(entry) => write!(f, "empty description for '{entry}'"),
+            Self::UnknownRuleGitmoji(gitmoji) => {
+                write!(f, "the rule gitmoji '{gitmoji}' is not in the catalog")
+            }
+        }
+    }
+}
+
+// A missing description is allowed, but not a blank one
+fn is_empty(description: Option<&str>) -> bool {
+    description.is_some_and(|it| it.trim().is_empty())
+}
+
+/// The issues of the catalog of the configuration
+pub(super) fn config_issues(config: &GitmojiConfig) -> Vec<ConfigIssue> {
+    let mut result = vec![];
+
+    let mut codes = HashSet::new();
+    for gitmoji in config.gitmojis() {
+        if !codes.insert(gitmoji.code()) {
+            result.push(ConfigIssue::DuplicateGitmojiCode(
+                gitmoji.code().to_string(),
+            ));
+        }
+        if is_empty(gitmoji.description()) {
+            result.push(ConfigIssue::EmptyDescription(gitmoji.code().to_string()));
+        }
+    }
+
+    let mut types = HashSet::new();
+    for emoji in config.conventional_commit_emojis() {
+        if !types.insert(emoji.r#type()) {
+            result.push(ConfigIssue::DuplicateConventionalType(
+                emoji.r#type().to_string(),
+            ));
+        }
+        if is_empty(emoji.description()) {
+            result.push(ConfigIssue::EmptyDescription(emoji.r#type().to_string()));
+        }
+    }
+
+    // Without a catalog, every gitmoji is unknown, the catalog is reported elsewhere
+    if config.gitmojis().is_empty() && config.conventional_commit_emojis().is_empty() {
+        return result;
+    }
+    for allowed in config.rules().iter().flat_map(GitmojiRule::gitmojis) {
+        let known = config
+            .gitmojis()
+            .iter()
+            .any(|it| is_same_gitmoji(allowed, it.emoji(), it.code(), it.name()))
+            || config
+                .conventional_commit_emojis()
+                .iter()
+                .any(|it| is_same_gitmoji(allowed, it.emoji(), it.code(), Some(it.r#type())));
+        if !known {
+            result.push(ConfigIssue::UnknownRuleGitmoji(allowed.clone()));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::check;
+
+    use super::*;
+    use crate::{ConventionalEmojiCommit, Gitmoji, LocalGitmojiConfig};
+
+    #[test]
+    fn should_find_issues() {
+        let gitmoji = |code: &str, description: Option<&str>| {
+            Gitmoji::new(
+                String::from("🎨"),
+                String::from(code),
+                None,
+                description.map(String::from),
+            )
+        };
+        let mut config = GitmojiConfig::default();
+        config.set_gitmojis(vec![
+            gitmoji(":art:", Some("Improve structure")),
+            gitmoji(":zap:", Some(" ")),
+            gitmoji(":art:", Some("Again")),
+        ]);
+        config.set_conventional_commit_emojis(
+            [(
+                String::from("feat"),
+                ConventionalEmojiCommit::new(
+                    String::from("✨"),
+                    String::from(":sparkles:"),
+                    String::from("feat"),
+                    Some(String::new()),
+                ),
+            )]
+            .into(),
+        );
+
+        let result = config_issues(&config);
+
+        check!(
+            result
+                == vec![
+                    ConfigIssue::EmptyDescription(String::from(":zap:")),
+                    ConfigIssue::DuplicateGitmojiCode(String::from(":art:")),
+                    ConfigIssue::EmptyDescription(String::from("feat")),
+                ]
+        );
+    }
+
+    #[test]
+    fn should_not_find_issues_in_default() {
+        let result = config_issues(&GitmojiConfig::default());
+
+        check!(result.is_empty());
+    }
+
+    #[test]
+    fn should_find_unknown_rule_gitmojis() {
+        let mut config = GitmojiConfig::default();
+        config.set_gitmojis(vec![Gitmoji::new(
+            String::from("📝"),
+            String::from(":memo:"),
+            Some(String::from("memo")),
+            None,
+        )]);
+        let local_config: LocalGitmojiConfig = toml_edit::de::from_str(
+            r#"
+[[rules]]
+paths = ["docs/"]
+gitmojis = ["📝", "memo", ":memo:", ":memos:"]
+"#,
+        )
+        .unwrap();
+        config.merge(&local_config);
+
+        let result = config_issues(&config);
+
+        check!(result == vec![ConfigIssue::UnknownRuleGitmoji(String::from(":memos:"))]);
+    }
+}
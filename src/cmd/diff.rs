@@ -0,0 +1,156 @@
+use std::fmt::{self, Display};
+
+use crate::{ConventionalEmojiCommit, Gitmoji, GitmojiConfig};
+
+/// A change of a catalog entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum CatalogChange {
+    /// A new entry
+    Added(String),
+    /// An entry not in the catalog anymore
+    Removed(String),
+    /// The fields changed, like `description: 'Old' → 'New'`
+    Changed { entry: String, fields: Vec<String> },
+}
+
+impl Display for CatalogChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added(entry) => write!(f, "➕ {entry}"),
+            Self::Removed(entry) => write!(f, "➖ {entry}"),
+            Self::Changed { entry, fields } => write!(f, "✏️  {entry}: {}", fields.join(", ")),
+        }
+    }
+}
+
+/// The changes between the catalogs of the configurations
+pub(super) fn catalog_changes(old: &GitmojiConfig, new: &GitmojiConfig) -> Vec<CatalogChange> {
+    let mut result = diff(
+        old.gitmojis(),
+        new.gitmojis(),
+        Gitmoji::code,
+        |gitmoji| format!("{} {}", gitmoji.emoji(), gitmoji.code()),
+        |old, new| {
+            [
+                field("emoji", Some(old.emoji()), Some(new.emoji())),
+                field("name", old.name(), new.name()),
+                field("description", old.description(), new.description()),
+                field("semver", old.semver(), new.semver()),
+            ]
+        },
+    );
+    result.extend(diff(
+        old.conventional_commit_emojis(),
+        new.conventional_commit_emojis(),
+        ConventionalEmojiCommit::r#type,
+        |emoji| format!("{} {}", emoji.emoji(), emoji.r#type()),
+        |old, new| {
+            [
+                field("emoji", Some(old.emoji()), Some(new.emoji())),
+                field("code", Some(old.code()), Some(new.code())),
+                field("description", old.description(), new.description()),
+            ]
+        },
+    ));
+    result
+}
+
+fn diff<T, const N: usize>(
+    old: &[T],
+    new: &[T],
+    key: impl Fn(&T) -> &str,
+    display: impl Fn(&T) -> String,
+    fields: impl Fn(&T, &T) -> [Option<String>; N],
+) -> Vec<CatalogChange> {
+    let mut result = vec![];
+    for entry in new {
+        match old.iter().find(|it| key(it) == key(entry)) {
+            None => result.push(CatalogChange::Added(display(entry))),
+            Some(previous) => {
+                let fields = fields(previous, entry)
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                if !fields.is_empty() {
+                    result.push(CatalogChange::Changed {
+                        entry: display(entry),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    for entry in old {
+        if !new.iter().any(|it| key(it) == key(entry)) {
+            result.push(CatalogChange::Removed(display(entry)));
+        }
+    }
+    result
+}
+
+fn field(name: &str, old: Option<&str>, new: Option<&str>) -> Option<String> {
+    (old != new).then(|| {
+        format!(
+            "{name} '{}' → '{}'",
+            old.unwrap_or_default(),
+            new.unwrap_or_default()
+        )
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::check;
+
+    use super::*;
+
+    fn gitmoji(emoji: &str, code: &str, description: &str) -> Gitmoji {
+        Gitmoji::new(
+            String::from(emoji),
+            String::from(code),
+            None,
+            Some(String::from(description)),
+        )
+    }
+
+    #[test]
+    fn should_list_catalog_changes() {
+        let mut old = GitmojiConfig::default();
+        old.set_gitmojis(vec![
+            gitmoji("🎨", ":art:", "Improve structure."),
+            gitmoji("⚡️", ":zap:", "Improve performance."),
+            gitmoji("🔥", ":fire:", "Remove code or files."),
+        ]);
+        let mut new = GitmojiConfig::default();
+        let mut zap = gitmoji("⚡️", ":zap:", "Improve the performance.");
+        zap.set_semver(Some(String::from("patch")));
+        new.set_gitmojis(vec![
+            gitmoji("🎨", ":art:", "Improve structure."),
+            zap,
+            gitmoji("🧵", ":thread:", "Add concurrency."),
+        ]);
+
+        let changes = catalog_changes(&old, &new)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        check!(
+            changes
+                == [
+                    "✏️  ⚡️ :zap:: description 'Improve performance.' → 'Improve the performance.', semver '' → 'patch'",
+                    "➕ 🧵 :thread:",
+                    "➖ 🔥 :fire:",
+                ]
+        );
+    }
+
+    #[test]
+    fn should_not_list_changes_of_same_catalog() {
+        let mut config = GitmojiConfig::default();
+        config.set_gitmojis(vec![gitmoji("🎨", ":art:", "Improve structure.")]);
+
+        check!(catalog_changes(&config, &config.clone()).is_empty());
+    }
+}
@@ -0,0 +1,414 @@
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+use time::OffsetDateTime;
+
+use super::config::GIT_CONFIG_LOCAL_FILE;
+use super::{check_config_file, get_config_file, get_local_config_file, read_config_or_default};
+use crate::{git, CatalogLocation, GitmojiConfig, Prompter};
+
+/// The catalog is considered stale after this number of days
+const CATALOG_MAX_AGE_DAYS: i64 = 30;
+
+/// The timeout of the network check
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The result of a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Status {
+    /// Everything is fine
+    Pass,
+    /// Works, but could be better
+    Warn,
+    /// Does not work
+    Fail,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "✅"),
+            Self::Warn => write!(f, "⚠️ "),
+            Self::Fail => write!(f, "❌"),
+        }
+    }
+}
+
+/// A check of `gitmoji doctor`, with the remediation when it does not pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Diagnosis {
+    status: Status,
+    name: &'static str,
+    message: String,
+    remediation: Option<String>,
+}
+
+impl Diagnosis {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status: Status::Pass,
+            name,
+            message: message.into(),
+            remediation: None,
+        }
+    }
+
+    fn warn(
+        name: &'static str,
+        message: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: Status::Warn,
+            name,
+            message: message.into(),
+            remediation: Some(remediation.into()),
+        }
+    }
+
+    fn fail(
+        name: &'static str,
+        message: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: Status::Fail,
+            name,
+            message: message.into(),
+            remediation: Some(remediation.into()),
+        }
+    }
+
+    /// The result of the check
+    pub(super) const fn status(&self) -> Status {
+        self.status
+    }
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.status, self.name, self.message)?;
+        if let Some(remediation) = &self.remediation {
+            write!(f, "\n   💡 {remediation}")?;
+        }
+        Ok(())
+    }
+}
+
+async fn check_git() -> Diagnosis {
+    match git::get_version().await {
+        Ok(version) => Diagnosis::pass("git", version),
+        Err(err) => Diagnosis::fail(
+            "git",
+            format!("cannot run git, {err}"),
+            "install git, see https://git-scm.com/downloads",
+        ),
+    }
+}
+
+fn check_repository(inside: bool) -> Diagnosis {
+    if inside {
+        Diagnosis::pass("repository", "inside a git repository")
+    } else {
+        Diagnosis::fail(
+            "repository",
+            "not inside a git repository",
+            "run gitmoji in a git repository, or create one with `git init`",
+        )
+    }
+}
+
+async fn check_config() -> Diagnosis {
+    let file = match get_config_file().await {
+        Ok(file) => file,
+        Err(err) => return Diagnosis::fail("config", err.to_string(), "check the HOME directory"),
+    };
+    if !file.exists() {
+        return Diagnosis::warn(
+            "config",
+            format!(
+                "{} not found, using the default preferences",
+                file.display()
+            ),
+            "run `gitmoji init`",
+        );
+    }
+    match check_config_file(&file, false).await {
+        Ok(()) => Diagnosis::pass("config", file.display().to_string()),
+        Err(err) => Diagnosis::fail(
+            "config",
+            err.to_string(),
+            "fix it with `gitmoji config edit`, then run `gitmoji config validate`",
+        ),
+    }
+}
+
+async fn check_local_config() -> Diagnosis {
+    let configured = git::get_config_value(GIT_CONFIG_LOCAL_FILE)
+        .await
+        .unwrap_or_default();
+    let file = match get_local_config_file().await {
+        Ok(file) => file,
+        Err(err) => {
+            return Diagnosis::fail(
+                "local config",
+                err.to_string(),
+                format!("check the `{GIT_CONFIG_LOCAL_FILE}` git config"),
+            );
+        }
+    };
+    if !file.exists() {
+        return if configured.is_empty() {
+            Diagnosis::pass("local config", "no repository configuration")
+        } else {
+            Diagnosis::warn(
+                "local config",
+                format!(
+                    "{} from `{GIT_CONFIG_LOCAL_FILE}` not found",
+                    file.display()
+                ),
+                format!("create the file, or run `git config --unset {GIT_CONFIG_LOCAL_FILE}`"),
+            )
+        };
+    }
+    match check_config_file(&file, true).await {
+        Ok(()) if configured.is_empty() => {
+            Diagnosis::pass("local config", file.display().to_string())
+        }
+        Ok(()) => Diagnosis::pass(
+            "local config",
+            format!("{} from `{GIT_CONFIG_LOCAL_FILE}`", file.display()),
+        ),
+        Err(err) => Diagnosis::fail(
+            "local config",
+            err.to_string(),
+            "fix it with `gitmoji config edit --local`, then run `gitmoji config validate`",
+        ),
+    }
+}
+
+fn check_catalog(config: &GitmojiConfig, now: OffsetDateTime) -> Diagnosis {
+    let count = config.gitmojis().len() + config.conventional_commit_emojis().len();
+    if count == 0 {
+        return Diagnosis::fail("catalog", "the catalog is empty", "run `gitmoji update`");
+    }
+    let Some(last_update) = config.last_update() else {
+        return Diagnosis::warn(
+            "catalog",
+            format!("{count} emoji(s), never updated"),
+            "run `gitmoji update`",
+        );
+    };
+    let days = (now - last_update).whole_days();
+    if days > CATALOG_MAX_AGE_DAYS {
+        Diagnosis::warn(
+            "catalog",
+            format!("{count} emoji(s), updated {days} days ago"),
+            "run `gitmoji update`",
+        )
+    } else {
+        Diagnosis::pass(
+            "catalog",
+            format!("{count} emoji(s), updated {days} day(s) ago"),
+        )
+    }
+}
+
+async fn check_network(config: &GitmojiConfig) -> Vec<Diagnosis> {
+    let client = match reqwest::Client::builder().timeout(NETWORK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            return vec![Diagnosis::warn(
+                "network",
+                err.to_string(),
+                "check the TLS certificates of the system",
+            )];
+        }
+    };
+    let mut result = vec![];
+    for source in config.catalog_sources() {
+        let CatalogLocation::Url { url } = source.location() else {
+            continue;
+        };
+        let response = client
+            .get(url.clone())
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+        let diagnosis = match response {
+            Ok(_) => Diagnosis::pass("network", format!("{url} is reachable")),
+            Err(err) => Diagnosis::warn(
+                "network",
+                format!("cannot reach {url}, {err}"),
+                "check the network and the proxy, `gitmoji update` needs it",
+            ),
+        };
+        result.push(diagnosis);
+    }
+    result
+}
+
+#[cfg(feature = "hook")]
+async fn check_hooks() -> Vec<Diagnosis> {
+    use super::hook::{self, HookState};
+
+    let statuses = match hook::status().await {
+        Ok(statuses) => statuses,
+        Err(err) => {
+            return vec![Diagnosis::fail(
+                "hook",
+                err.to_string(),
+                "check the `core.hooksPath` git config",
+            )];
+        }
+    };
+    statuses
+        .into_iter()
+        .map(|status| {
+            let path = status.path().display();
+            match status.state() {
+                HookState::Missing => Diagnosis::warn(
+                    "hook",
+                    format!("{path} is not installed"),
+                    "run `gitmoji hook add`",
+                ),
+                HookState::Managed {
+                    executable: false, ..
+                } => Diagnosis::fail(
+                    "hook",
+                    format!("{path} is not executable"),
+                    format!("run `chmod +x {path}`"),
+                ),
+                HookState::Managed { .. } => {
+                    Diagnosis::pass("hook", format!("{path} is installed"))
+                }
+                HookState::Foreign => Diagnosis::warn(
+                    "hook",
+                    format!("{path} is not managed by gitmoji"),
+                    "run `gitmoji hook add --chain` to call it before gitmoji",
+                ),
+            }
+        })
+        .collect()
+}
+
+// GnuPG can find the key of the committer email, but SSH needs the key
+async fn check_signing() -> Diagnosis {
+    let key = git::get_config_value("user.signingkey")
+        .await
+        .unwrap_or_default();
+    let format = git::get_config_value("gpg.format")
+        .await
+        .unwrap_or_default();
+    if !key.is_empty() {
+        return Diagnosis::pass("signing", format!("commits are signed with {key}"));
+    }
+    if format == "ssh" {
+        Diagnosis::fail(
+            "signing",
+            "`signed` is set, but `user.signingkey` is missing for SSH",
+            "run `git config user.signingkey ~/.ssh/id_ed25519.pub`",
+        )
+    } else {
+        Diagnosis::warn(
+            "signing",
+            "`signed` is set, but `user.signingkey` is missing, GnuPG uses the committer email",
+            "run `git config user.signingkey <KEY ID>`",
+        )
+    }
+}
+
+fn check_terminal(interactive: bool) -> Diagnosis {
+    if interactive {
+        Diagnosis::pass("terminal", "the prompts are available")
+    } else {
+        Diagnosis::warn(
+            "terminal",
+            "no terminal, the prompts are not available",
+            "run gitmoji in a terminal, a hook manager should redirect the input from `/dev/tty`",
+        )
+    }
+}
+
+/// Check the environment of gitmoji, the network check is skipped when `offline`
+pub(super) async fn diagnose(offline: bool, prompter: &dyn Prompter) -> Vec<Diagnosis> {
+    let mut result = vec![check_git().await];
+    let inside = git::is_inside_work_tree().await;
+    result.push(check_repository(inside));
+    result.push(check_config().await);
+    if inside {
+        result.push(check_local_config().await);
+    }
+
+    // An invalid configuration is already reported
+    if let Ok(config) = read_config_or_default().await {
+        result.push(check_catalog(&config, OffsetDateTime::now_utc()));
+        if !offline {
+            result.extend(check_network(&config).await);
+        }
+        if config.signed() {
+            result.push(check_signing().await);
+        }
+    }
+
+    #[cfg(feature = "hook")]
+    if inside {
+        result.extend(check_hooks().await);
+    }
+    result.push(check_terminal(prompter.is_interactive()));
+
+    result
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::check;
+    use time::Duration;
+
+    use super::*;
+    use crate::Gitmoji;
+
+    fn config() -> GitmojiConfig {
+        let mut config = GitmojiConfig::default();
+        config.set_gitmojis(vec![Gitmoji::new(
+            String::from("🎨"),
+            String::from(":art:"),
+            None,
+            None,
+        )]);
+        config
+    }
+
+    #[test]
+    fn should_pass_fresh_catalog() {
+        let config = config();
+        let now = config.last_update().unwrap() + Duration::days(2);
+
+        let result = check_catalog(&config, now);
+
+        check!(result.status() == Status::Pass);
+        check!(result.to_string() == "✅ catalog: 1 emoji(s), updated 2 day(s) ago");
+    }
+
+    #[test]
+    fn should_warn_stale_catalog() {
+        let config = config();
+        let now = config.last_update().unwrap() + Duration::days(45);
+
+        let result = check_catalog(&config, now);
+
+        check!(result.status() == Status::Warn);
+        check!(
+            result.to_string()
+                == "⚠️  catalog: 1 emoji(s), updated 45 days ago\n   💡 run `gitmoji update`"
+        );
+    }
+
+    #[test]
+    fn should_fail_empty_catalog() {
+        let result = check_catalog(&GitmojiConfig::default(), OffsetDateTime::now_utc());
+
+        check!(result.status() == Status::Fail);
+    }
+}
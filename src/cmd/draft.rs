@@ -0,0 +1,44 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use tokio::fs;
+use tracing::debug;
+
+use crate::{git, CommitMessage, Result};
+
+/// The draft file, in the git directory
+const DRAFT_FILE: &str = "GITMOJI_DRAFT";
+
+async fn draft_path() -> Result<PathBuf> {
+    let result = git::get_git_dir().await?.join(DRAFT_FILE);
+    Ok(result)
+}
+
+/// Save the message before committing, to resume it if the commit fails
+pub(super) async fn save_draft(message: &CommitMessage) -> Result<()> {
+    let path = draft_path().await?;
+    debug!("Save the draft in {path:?}");
+    fs::write(&path, message.to_string()).await?;
+    Ok(())
+}
+
+/// Read the saved draft, if any
+pub(super) async fn read_draft() -> Result<Option<CommitMessage>> {
+    let path = draft_path().await?;
+    let text = match fs::read_to_string(&path).await {
+        Ok(text) => text,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let message = text.parse()?;
+    Ok(Some(message))
+}
+
+/// Remove the draft once committed
+pub(super) async fn remove_draft() -> Result<()> {
+    let path = draft_path().await?;
+    match fs::remove_file(&path).await {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
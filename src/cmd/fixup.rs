@@ -0,0 +1,117 @@
+use crate::{FixupKind, GitmojiConfig};
+
+/// Render the gitmoji code starting the subject as an emoji
+pub(super) fn render_subject(config: &GitmojiConfig, subject: &str) -> String {
+    let Some(prefix) = config.find_gitmoji_prefix(subject) else {
+        return subject.to_string();
+    };
+    let gitmojis = config
+        .gitmojis()
+        .iter()
+        .map(|gitmoji| (gitmoji.code(), gitmoji.emoji()));
+    let conventional_commit_emojis = config
+        .conventional_commit_emojis()
+        .iter()
+        .map(|emoji| (emoji.code(), emoji.emoji()));
+    let emoji = gitmojis
+        .chain(conventional_commit_emojis)
+        .find_map(|(code, emoji)| (code == prefix).then_some(emoji));
+
+    emoji.map_or_else(
+        || subject.to_string(),
+        |emoji| format!("{emoji}{}", &subject[prefix.len()..]),
+    )
+}
+
+/// The `git commit` arguments for the fixup commit
+///
+/// The `message` is the squash message, or the new message of an amend.
+pub(super) fn fixup_args(
+    kind: FixupKind,
+    target: (&str, &str),
+    all: bool,
+    signed: bool,
+    message: Option<&str>,
+    extra_args: &[String],
+) -> Vec<String> {
+    let (id, subject) = target;
+    let mut args = vec![String::from("commit")];
+    if all {
+        args.push(String::from("--all"));
+    }
+    if signed {
+        args.push(String::from("-S"));
+    }
+    match kind {
+        FixupKind::Fixup => args.push(format!("--fixup={id}")),
+        FixupKind::Squash => args.push(format!("--squash={id}")),
+        // Like `git commit --fixup=amend:<id>`, without the editor, and maybe without changes
+        FixupKind::Amend => {
+            args.push(String::from("--allow-empty"));
+            args.push(String::from("-m"));
+            args.push(format!("amend! {subject}"));
+        }
+    }
+    if let Some(message) = message.filter(|message| !message.is_empty()) {
+        args.push(String::from("-m"));
+        args.push(message.to_string());
+    }
+    args.extend_from_slice(extra_args);
+    args
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::check;
+
+    use super::*;
+    use crate::Gitmoji;
+
+    #[test]
+    fn should_render_subject_with_emoji() {
+        let mut config = GitmojiConfig::default();
+        config.set_gitmojis(vec![Gitmoji::new(
+            String::from("⚡️"),
+            String::from(":zap:"),
+            Some(String::from("zap")),
+            None,
+        )]);
+
+        check!(render_subject(&config, ":zap: Faster") == "⚡️ Faster");
+        check!(render_subject(&config, "⚡️ Faster") == "⚡️ Faster");
+        check!(render_subject(&config, "Faster") == "Faster");
+    }
+
+    #[test]
+    fn should_build_amend_args() {
+        let args = fixup_args(
+            FixupKind::Amend,
+            ("abc123", ":zap: Faster"),
+            false,
+            true,
+            Some(":zap: Much faster"),
+            &[String::from("--no-verify")],
+        );
+
+        check!(
+            args == [
+                "commit",
+                "-S",
+                "--allow-empty",
+                "-m",
+                "amend! :zap: Faster",
+                "-m",
+                ":zap: Much faster",
+                "--no-verify"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_build_fixup_args() {
+        let args = fixup_args(FixupKind::Fixup, ("abc123", "Plop"), true, false, None, &[]);
+
+        check!(args == ["commit", "--all", "--fixup=abc123"]);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{Error, Result};
+
+/// The options providing the commit message, replaced by gitmoji
+const MESSAGE_OPTIONS: &[&str] = &[
+    "-m",
+    "--message",
+    "-F",
+    "--file",
+    "-C",
+    "--reuse-message",
+    "-c",
+    "--reedit-message",
+    "-t",
+    "--template",
+];
+
+/// The options taking their value in the next argument
+const OPTIONS_WITH_VALUE: &[&str] = &[
+    "--author",
+    "--date",
+    "--cleanup",
+    "--trailer",
+    "--fixup",
+    "--squash",
+    "--pathspec-from-file",
+];
+
+/// The options selecting the changes, conflicting with `--all`
+const SELECTION_OPTIONS: &[&str] = &[
+    "-i",
+    "--include",
+    "-o",
+    "--only",
+    "-p",
+    "--patch",
+    "--interactive",
+    "--pathspec-from-file",
+];
+
+/// The extra arguments forwarded to `git commit`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct GitArgs {
+    args: Vec<String>,
+    signing: bool,
+    pathspecs: bool,
+    /// With `--allow-empty`, or `--amend` reusing the previous changes
+    allow_no_changes: bool,
+}
+
+impl GitArgs {
+    /// Check the arguments do not conflict with the gitmoji options
+    pub(super) fn parse(args: Vec<String>, all: bool) -> Result<Self> {
+        let mut result = Self::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let (name, value) = arg
+                .split_once('=')
+                .map_or((arg.as_str(), None), |(name, value)| (name, Some(value)));
+            if arg == "--" {
+                result.pathspecs |= iter.next().is_some();
+                break;
+            } else if !arg.starts_with('-') {
+                result.pathspecs = true;
+            } else if MESSAGE_OPTIONS.contains(&name) || is_short_with_value(arg, MESSAGE_OPTIONS) {
+                return Err(conflict(format!(
+                    "'{arg}' conflicts with the gitmoji message"
+                )));
+            } else if name == "--fixup" {
+                let value = value.or_else(|| iter.next().map(String::as_str));
+                if value.is_some_and(|value| {
+                    value.starts_with("amend:") || value.starts_with("reword:")
+                }) {
+                    return Err(conflict(format!(
+                        "'{arg}' replaces the message, use `gitmoji fixup` instead"
+                    )));
+                }
+            } else if OPTIONS_WITH_VALUE.contains(&name) && value.is_none() {
+                iter.next();
+            } else if arg.starts_with("-S") || name == "--gpg-sign" || name == "--no-gpg-sign" {
+                result.signing = true;
+            } else if name == "--allow-empty" || name == "--amend" {
+                result.allow_no_changes = true;
+            }
+            if all && SELECTION_OPTIONS.contains(&name) {
+                return Err(conflict(format!("'{arg}' conflicts with '--all'")));
+            }
+        }
+        if all && result.pathspecs {
+            return Err(conflict(String::from("paths conflict with '--all'")));
+        }
+
+        result.args = args;
+        Ok(result)
+    }
+
+    /// The arguments
+    pub(super) fn args(&self) -> &[String] {
+        self.args.as_ref()
+    }
+
+    /// If the signature is configured by the arguments
+    pub(super) const fn signing(&self) -> bool {
+        self.signing
+    }
+
+    /// If the commit does not need staged changes
+    pub(super) const fn skip_staged_check(&self) -> bool {
+        self.pathspecs || self.allow_no_changes
+    }
+}
+
+// Like `-mMessage` or `-Ffile`
+fn is_short_with_value(arg: &str, options: &[&str]) -> bool {
+    options
+        .iter()
+        .filter(|option| option.len() == 2)
+        .any(|option| arg.starts_with(option))
+}
+
+fn conflict(message: String) -> Error {
+    Error::InvalidGitArguments(message)
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn should_forward_arguments() {
+        let result = GitArgs::parse(
+            args(&[
+                "--no-verify",
+                "--author",
+                "Jane <jane@example.com>",
+                "--date=2024-01-01",
+                "--trailer",
+                "Refs: #42",
+                "--fixup",
+                "HEAD~1",
+                "-SABCDEF",
+            ]),
+            true,
+        );
+
+        let_assert!(Ok(git_args) = result);
+        check!(git_args.args().len() == 9);
+        check!(git_args.signing());
+        check!(!git_args.skip_staged_check());
+    }
+
+    #[test]
+    fn should_detect_pathspecs() {
+        let result = GitArgs::parse(args(&["--no-verify", "--", "src/"]), false);
+
+        let_assert!(Ok(git_args) = result);
+        check!(git_args.skip_staged_check());
+    }
+
+    #[test]
+    fn should_detect_amend() {
+        let result = GitArgs::parse(args(&["--amend", "--no-edit"]), false);
+
+        let_assert!(Ok(git_args) = result);
+        check!(git_args.skip_staged_check());
+    }
+
+    #[test]
+    fn should_reject_message_options() {
+        for arg in ["-m", "-mPlop", "--message=Plop", "-F", "--template"] {
+            let result = GitArgs::parse(args(&[arg]), false);
+
+            let_assert!(Err(Error::InvalidGitArguments(_)) = result);
+        }
+    }
+
+    #[test]
+    fn should_reject_conflicts_with_all() {
+        for arg in ["--patch", "--only", "src/main.rs"] {
+            let result = GitArgs::parse(args(&[arg]), true);
+
+            let_assert!(Err(Error::InvalidGitArguments(_)) = result);
+        }
+    }
+
+    #[test]
+    fn should_reject_amend_fixup() {
+        let result = GitArgs::parse(args(&["--fixup=amend:HEAD"]), false);
+
+        let_assert!(Err(Error::InvalidGitArguments(_)) = result);
+    }
+}
@@ -0,0 +1,485 @@
+use std::fmt::Write;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use tokio::fs;
+use tracing::info;
+
+use super::super::validate::check_subject;
+use super::super::{ask_commit_message, pick_conventional_emoji, pick_gitmoji};
+use crate::{git, CommitSpecification, EmojiFormat, Error, GitmojiConfig, Prompter, Result};
+
+const PLACEHOLDER_SUBJECT: &str = "<gitmoji> <commit title>";
+const DEFAULT_COMMENT_CHAR: &str = "#";
+const SCISSORS: &str = " ------------------------ >8 ------------------------";
+
+/// The files of the git dir while git commits a merge or a revert
+const GIT_OPERATION_HEADS: [&str; 2] = ["MERGE_HEAD", "REVERT_HEAD"];
+
+/// The subject prefixes of the messages generated by git
+const GENERATED_SUBJECT_PREFIXES: [&str; 5] =
+    ["Merge ", "Revert \"", "fixup! ", "squash! ", "amend! "];
+
+/// The source of the commit message, as provided by git to the `prepare-commit-msg` hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommitSource {
+    /// A plain `git commit`
+    Empty,
+    /// A `-m` or `-F` option was given
+    Message,
+    /// A `-t` option was given, or `commit.template` is set
+    Template,
+    /// A merge commit, or a `.git/MERGE_MSG` file exists
+    Merge,
+    /// A `.git/SQUASH_MSG` file exists
+    Squash,
+    /// A `-c`, `-C` or `--amend` option was given
+    Commit,
+}
+
+impl From<Option<&str>> for CommitSource {
+    fn from(source: Option<&str>) -> Self {
+        match source {
+            Some("message") => Self::Message,
+            Some("template") => Self::Template,
+            Some("merge") => Self::Merge,
+            Some("squash") => Self::Squash,
+            Some("commit") => Self::Commit,
+            _ => Self::Empty,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PickedGitmoji {
+    emoji: String,
+    type_name: Option<String>,
+}
+
+impl PickedGitmoji {
+    /// Prefix the subject, replacing the existing gitmoji (and conventional type)
+    fn apply_to(&self, subject: &str, config: &GitmojiConfig) -> String {
+        let rest = config
+            .find_gitmoji_prefix(subject)
+            .map_or(subject, |prefix| &subject[prefix.len()..])
+            .trim_start();
+        let Self { emoji, type_name } = self;
+        match type_name {
+            Some(type_name) => match conventional_header_len(rest) {
+                Some((type_len, _)) => format!("{emoji}{type_name}{}", &rest[type_len..]),
+                None => format!("{emoji}{type_name}: {rest}"),
+            },
+            None => format!("{emoji} {rest}"),
+        }
+    }
+}
+
+// Find the `type(scope)!: ` header, returns the length of the type and of the whole header
+fn conventional_header_len(subject: &str) -> Option<(usize, usize)> {
+    let type_len = subject
+        .find(|ch: char| !(ch.is_alphanumeric() || ch == '-' || ch == '_'))
+        .filter(|len| *len > 0)?;
+    let mut rest = &subject[type_len..];
+    if rest.starts_with('(') {
+        let end = rest.find(')')?;
+        rest = &rest[end + 1..];
+    }
+    rest = rest.strip_prefix('!').unwrap_or(rest);
+    rest = rest.strip_prefix(':')?;
+    let rest = rest.trim_start();
+
+    Some((type_len, subject.len() - rest.len()))
+}
+
+fn ask_gitmoji(config: &GitmojiConfig, prompter: &dyn Prompter) -> Result<PickedGitmoji> {
+    let result = match config.specification() {
+        CommitSpecification::Default => {
+            let gitmoji = pick_gitmoji(config, None, prompter)?;
+            let emoji = match config.format() {
+                EmojiFormat::UseCode => gitmoji.code(),
+                EmojiFormat::UseEmoji => gitmoji.emoji(),
+            };
+            PickedGitmoji {
+                emoji: emoji.to_string(),
+                type_name: None,
+            }
+        }
+        CommitSpecification::ConventionalEmojiCommits => {
+            let emoji = pick_conventional_emoji(config, None, prompter)?;
+            let type_name = Some(emoji.r#type().to_string());
+            let emoji = match config.format() {
+                EmojiFormat::UseCode => emoji.code(),
+                EmojiFormat::UseEmoji => emoji.emoji(),
+            };
+            PickedGitmoji {
+                emoji: emoji.to_string(),
+                type_name,
+            }
+        }
+    };
+
+    Ok(result)
+}
+
+/// Write the commit message, or a template when we cannot prompt
+///
+/// Merges and squashes are left untouched, an existing message (`-m`, `--amend`)
+/// only gets its gitmoji picked.
+pub async fn apply(
+    dest: &Path,
+    source: Option<&str>,
+    config: &GitmojiConfig,
+    scopes: &[String],
+    prompter: &dyn Prompter,
+) -> Result<()> {
+    let source = CommitSource::from(source);
+    if matches!(source, CommitSource::Merge | CommitSource::Squash) {
+        info!("Skip the gitmoji hook for a {source:?} commit");
+        return Ok(());
+    }
+
+    let contents = match fs::read_to_string(dest).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let comment_char = comment_char().await?;
+    let interactive = prompter.is_interactive();
+
+    let message = match source {
+        CommitSource::Message | CommitSource::Commit => {
+            if !interactive {
+                info!("No TTY available, keep the {source:?} commit message");
+                return Ok(());
+            }
+            if source == CommitSource::Message
+                && has_gitmoji_subject(&contents, &comment_char, config)
+            {
+                info!("The commit message already starts with a gitmoji");
+                return Ok(());
+            }
+            let gitmoji = ask_gitmoji(config, prompter)?;
+            replace_subject(&contents, &comment_char, |subject| {
+                gitmoji.apply_to(subject, config)
+            })
+        }
+        _ if interactive => {
+            let message = ask_commit_message(config, scopes, None, prompter).await?;
+            let header = message.description().map_or_else(
+                || format!("{}\n\n", message.header()),
+                |description| format!("{}\n\n{description}\n", message.header()),
+            );
+            format!("{header}{contents}")
+        }
+        _ => {
+            info!("No TTY available, write a gitmoji template instead of prompting");
+            format!("{}{contents}", template(config, &comment_char))
+        }
+    };
+
+    info!("Write commit message to {dest:?} with source: {source:?}");
+    fs::write(dest, message).await?;
+
+    Ok(())
+}
+
+// With `auto`, git picks a character unused by the message, usually `#`
+async fn comment_char() -> Result<String> {
+    let result = git::get_config_value("core.commentChar").await?;
+    if result.is_empty() || result == "auto" {
+        Ok(String::from(DEFAULT_COMMENT_CHAR))
+    } else {
+        Ok(result)
+    }
+}
+
+fn is_subject_line(line: &str, comment_char: &str) -> bool {
+    !line.trim().is_empty() && !line.starts_with(comment_char)
+}
+
+fn has_gitmoji_subject(contents: &str, comment_char: &str, config: &GitmojiConfig) -> bool {
+    contents
+        .lines()
+        .find(|line| is_subject_line(line, comment_char))
+        .and_then(|subject| config.find_gitmoji_prefix(subject))
+        .is_some()
+}
+
+fn replace_subject(contents: &str, comment_char: &str, f: impl FnOnce(&str) -> String) -> String {
+    let mut lines = contents.lines().map(str::to_string).collect::<Vec<_>>();
+    match lines
+        .iter_mut()
+        .find(|line| is_subject_line(line, comment_char))
+    {
+        Some(subject) => *subject = f(subject),
+        None => lines.insert(0, f("")),
+    }
+    let mut result = lines.join("\n");
+    result.push('\n');
+
+    result
+}
+
+/// Check the commit message, without its comments, starts with a gitmoji
+///
+/// The merges and the reverts in progress, and the messages generated by git are not checked.
+/// The message file is never written, git applies its own `--cleanup` mode.
+pub async fn validate(
+    dest: &Path,
+    config: &GitmojiConfig,
+    branch: Option<&str>,
+    paths: &[String],
+) -> Result<()> {
+    let git_dir = git::get_git_dir().await?;
+    if let Some(head) = GIT_OPERATION_HEADS
+        .into_iter()
+        .find(|head| git_dir.join(head).exists())
+    {
+        info!("Skip the validation, {head} exists");
+        return Ok(());
+    }
+
+    let contents = fs::read_to_string(dest).await?;
+    let message = strip_comments(&contents, &comment_char().await?);
+    check_message(&message, config, branch, paths)
+}
+
+fn template(config: &GitmojiConfig, comment_char: &str) -> String {
+    let mut result = format!(
+        "{PLACEHOLDER_SUBJECT}\n\n\
+        {comment_char} Replace the first line by a gitmoji followed by the commit title\n\
+        {comment_char} Available gitmojis:\n"
+    );
+    let items: Vec<String> = match config.specification() {
+        CommitSpecification::Default => config.gitmojis().iter().map(ToString::to_string).collect(),
+        CommitSpecification::ConventionalEmojiCommits => config
+            .conventional_commit_emojis()
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    };
+    for item in items {
+        let _ = writeln!(result, "{comment_char}   {item}");
+    }
+    let _ = writeln!(result, "{comment_char}");
+
+    result
+}
+
+fn strip_comments(contents: &str, comment_char: &str) -> String {
+    let scissors = format!("{comment_char}{SCISSORS}");
+    let lines = contents
+        .lines()
+        .take_while(|line| *line != scissors)
+        .filter(|line| !line.starts_with(comment_char))
+        .map(str::trim_end)
+        .collect::<Vec<_>>();
+    let message = lines.join("\n");
+    let message = message.trim();
+
+    if message.is_empty() {
+        String::new()
+    } else {
+        format!("{message}\n")
+    }
+}
+
+fn check_message(
+    message: &str,
+    config: &GitmojiConfig,
+    branch: Option<&str>,
+    paths: &[String],
+) -> Result<()> {
+    let subject = message.lines().next().unwrap_or_default();
+    if subject.is_empty() {
+        return Err(Error::InvalidCommitMessage(String::from(
+            "the commit message is empty",
+        )));
+    }
+    if GENERATED_SUBJECT_PREFIXES
+        .iter()
+        .any(|prefix| subject.starts_with(prefix))
+    {
+        info!("Skip the validation of the message generated by git");
+        return Ok(());
+    }
+    if subject == PLACEHOLDER_SUBJECT {
+        return Err(Error::InvalidCommitMessage(format!(
+            "the placeholder '{PLACEHOLDER_SUBJECT}' should be replaced"
+        )));
+    }
+
+    check_subject(config, subject, branch, paths)
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+    use crate::{Answer, Gitmoji, ScriptedPrompter};
+
+    fn config() -> GitmojiConfig {
+        let mut config = GitmojiConfig::default();
+        config.set_gitmojis(vec![Gitmoji::new(
+            String::from("⚡️"),
+            String::from(":zap:"),
+            Some(String::from("zap")),
+            Some(String::from("Improve performance.")),
+        )]);
+        config
+    }
+
+    #[test]
+    fn should_build_template_with_gitmojis() {
+        let result = template(&config(), "#");
+
+        check!(result.starts_with(PLACEHOLDER_SUBJECT));
+        check!(result.contains("#   ⚡️ :zap: zap - Improve performance."));
+    }
+
+    #[test]
+    fn should_strip_comments() {
+        let contents = "\
+:zap: Faster
+
+Some details
+# Please enter the commit message for your changes.
+# ------------------------ >8 ------------------------
+diff --git a/file b/file
+";
+
+        let result = strip_comments(contents, "#");
+
+        check!(result == ":zap: Faster\n\nSome details\n");
+    }
+
+    #[test]
+    fn should_strip_comments_with_comment_char() {
+        let contents = "\
+:zap: Faster
+
+#1 is fixed
+; Please enter the commit message for your changes.
+; ------------------------ >8 ------------------------
+diff --git a/file b/file
+";
+
+        let result = strip_comments(contents, ";");
+
+        check!(result == ":zap: Faster\n\n#1 is fixed\n");
+    }
+
+    #[test]
+    fn should_accept_message_generated_by_git() {
+        let config = config();
+
+        check!(check_message("Merge branch 'feat'\n", &config, None, &[]).is_ok());
+        check!(check_message("Revert \"Faster\"\n", &config, None, &[]).is_ok());
+        check!(check_message("fixup! :zap: Faster\n", &config, None, &[]).is_ok());
+        check!(check_message("squash! :zap: Faster\n", &config, None, &[]).is_ok());
+    }
+
+    #[test]
+    fn should_accept_message_with_gitmoji() {
+        let config = config();
+
+        check!(check_message(":zap: Faster\n", &config, None, &[]).is_ok());
+        check!(check_message("⚡️ Faster\n", &config, None, &[]).is_ok());
+        check!(check_message("⚡ Faster\n", &config, None, &[]).is_ok());
+    }
+
+    #[test]
+    fn should_reject_invalid_message() {
+        let config = config();
+
+        let_assert!(Err(Error::InvalidCommitMessage(_)) = check_message("", &config, None, &[]));
+        let_assert!(
+            Err(Error::InvalidCommitMessage(_)) =
+                check_message(PLACEHOLDER_SUBJECT, &config, None, &[])
+        );
+        let_assert!(
+            Err(Error::InvalidCommitMessage(_)) = check_message("Faster\n", &config, None, &[])
+        );
+    }
+
+    #[test]
+    fn should_parse_commit_source() {
+        check!(CommitSource::from(None) == CommitSource::Empty);
+        check!(CommitSource::from(Some("message")) == CommitSource::Message);
+        check!(CommitSource::from(Some("merge")) == CommitSource::Merge);
+        check!(CommitSource::from(Some("commit")) == CommitSource::Commit);
+    }
+
+    #[test]
+    fn should_replace_existing_gitmoji() {
+        let config = config();
+        let gitmoji = PickedGitmoji {
+            emoji: String::from(":bug:"),
+            type_name: None,
+        };
+
+        check!(gitmoji.apply_to("Fix the build", &config) == ":bug: Fix the build");
+        check!(gitmoji.apply_to(":zap: Fix the build", &config) == ":bug: Fix the build");
+        check!(gitmoji.apply_to("⚡️ Fix the build", &config) == ":bug: Fix the build");
+    }
+
+    #[test]
+    fn should_replace_existing_conventional_type() {
+        let config = config();
+        let gitmoji = PickedGitmoji {
+            emoji: String::from("🐛"),
+            type_name: Some(String::from("fix")),
+        };
+
+        check!(gitmoji.apply_to("Fix the build", &config) == "🐛fix: Fix the build");
+        check!(
+            gitmoji.apply_to("⚡️perf(core)!: Fix the build", &config)
+                == "🐛fix(core)!: Fix the build"
+        );
+    }
+
+    #[test]
+    fn should_replace_subject_after_comments() {
+        let contents = "# A comment\nOld subject\n\nBody\n";
+
+        let result = replace_subject(contents, "#", |subject| format!(":zap: {subject}"));
+
+        check!(result == "# A comment\n:zap: Old subject\n\nBody\n");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_apply_prompted_message() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let dest = dir.path().join("COMMIT_EDITMSG");
+        fs::write(&dest, "# Please enter the commit message\n")
+            .await
+            .unwrap();
+        let prompter = ScriptedPrompter::new([
+            Answer::Default,
+            Answer::Text(String::from("Faster build")),
+            Answer::Default,
+        ]);
+
+        let result = apply(&dest, None, &config(), &[], &prompter).await;
+
+        let_assert!(Ok(()) = result);
+        let contents = fs::read_to_string(&dest).await.unwrap();
+        check!(contents == ":zap: Faster build\n\n# Please enter the commit message\n");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_pick_gitmoji_for_message() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let dest = dir.path().join("COMMIT_EDITMSG");
+        fs::write(&dest, "Faster build\n").await.unwrap();
+        let prompter = ScriptedPrompter::new([Answer::Text(String::from("zap"))]);
+
+        let result = apply(&dest, Some("message"), &config(), &[], &prompter).await;
+
+        let_assert!(Ok(()) = result);
+        let contents = fs::read_to_string(&dest).await.unwrap();
+        check!(contents == ":zap: Faster build\n");
+    }
+}
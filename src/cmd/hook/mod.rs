@@ -0,0 +1,253 @@
+use std::fmt::{self, Display};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use tokio::fs;
+use tracing::{info, warn};
+
+use self::script::{is_executable, is_managed, script, set_executable, GitHook};
+use crate::{git, Error, HookManager, Result};
+
+mod message;
+mod script;
+
+pub use self::message::{apply, validate};
+
+const BACKUP_SUFFIX: &str = ".pre-gitmoji";
+
+/// The state of an installed hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookState {
+    /// No hook file
+    Missing,
+    /// The hook is managed by gitmoji
+    Managed {
+        /// The previous hook called before gitmoji
+        chained: Option<PathBuf>,
+        /// The hook file can be executed
+        executable: bool,
+    },
+    /// Another hook is installed
+    Foreign,
+}
+
+/// The status of a git hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookStatus {
+    path: PathBuf,
+    state: HookState,
+}
+
+impl HookStatus {
+    /// The hook file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The hook state
+    #[must_use]
+    pub const fn state(&self) -> &HookState {
+        &self.state
+    }
+}
+
+impl Display for HookStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { path, state } = self;
+        let path = path.display();
+        match state {
+            HookState::Missing => write!(f, "❌ {path} is not installed"),
+            HookState::Managed {
+                executable: false, ..
+            } => write!(f, "⚠️  {path} is installed but not executable"),
+            HookState::Managed {
+                chained: Some(chained),
+                ..
+            } => write!(
+                f,
+                "✅ {path} is installed, chained with {}",
+                chained.display()
+            ),
+            HookState::Managed { chained: None, .. } => write!(f, "✅ {path} is installed"),
+            HookState::Foreign => write!(f, "⚠️  {path} is not managed by gitmoji"),
+        }
+    }
+}
+
+/// Install the hooks
+///
+/// An existing hook that is not managed by gitmoji is kept as a backup and
+/// called before gitmoji when `chain` is set, otherwise the installation fails.
+pub async fn create(chain: bool) -> Result<()> {
+    let hooks_path = get_hooks_path().await?;
+    fs::create_dir_all(&hooks_path).await?;
+
+    // Check all hooks before writing anything
+    let mut hooks = vec![];
+    for hook in GitHook::ALL {
+        let path = hooks_path.join(hook.filename());
+        let backup = backup_path(&path);
+        let state = read_hook_state(&path).await?;
+        match state {
+            HookState::Foreign if !chain => return Err(Error::HookAlreadyExists(path)),
+            HookState::Foreign if fs::try_exists(&backup).await? => {
+                return Err(Error::HookAlreadyExists(backup));
+            }
+            _ => hooks.push((hook, path, backup, state)),
+        }
+    }
+
+    let binary = std::env::current_exe()?;
+    for (hook, path, backup, state) in hooks {
+        let chained = match state {
+            HookState::Missing => None,
+            HookState::Managed { chained, .. } => chained,
+            HookState::Foreign => {
+                info!("Backup the existing hook {path:?} to {backup:?}");
+                fs::rename(&path, &backup).await?;
+                Some(backup)
+            }
+        };
+
+        info!("Create hook in {path:?}");
+        fs::write(&path, script(hook, &binary, chained.as_deref())).await?;
+        set_executable(&path).await?;
+    }
+
+    Ok(())
+}
+
+/// Remove the hooks managed by gitmoji, and restore the backups
+///
+/// A hook that is not managed by gitmoji is kept.
+pub async fn remove() -> Result<()> {
+    let hooks_path = get_hooks_path().await?;
+
+    for hook in GitHook::ALL {
+        let path = hooks_path.join(hook.filename());
+        match read_hook_state(&path).await? {
+            HookState::Managed { chained, .. } => {
+                info!("Removing hook in {path:?}");
+                fs::remove_file(&path).await?;
+                if let Some(backup) = chained {
+                    info!("Restore the hook {backup:?}");
+                    fs::rename(&backup, &path).await?;
+                }
+            }
+            HookState::Foreign => warn!("The hook {path:?} is not managed by gitmoji, it is kept"),
+            HookState::Missing => info!("No hook to remove in {path:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// The status of the hooks
+pub async fn status() -> Result<Vec<HookStatus>> {
+    let hooks_path = get_hooks_path().await?;
+    let mut result = vec![];
+    for hook in GitHook::ALL {
+        let path = hooks_path.join(hook.filename());
+        let state = read_hook_state(&path).await?;
+        result.push(HookStatus { path, state });
+    }
+
+    Ok(result)
+}
+
+/// The configuration snippet to use gitmoji with a hook manager
+#[must_use]
+pub fn snippet(manager: HookManager) -> String {
+    let repository = env!("CARGO_PKG_REPOSITORY");
+    let version = env!("CARGO_PKG_VERSION");
+    match manager {
+        HookManager::PreCommit => format!(
+            "\
+# .pre-commit-config.yaml
+default_install_hook_types: [prepare-commit-msg, commit-msg]
+repos:
+  - repo: {repository}
+    rev: v{version}
+    hooks:
+      - id: gitmoji
+      - id: gitmoji-validate
+"
+        ),
+        HookManager::Husky => String::from(
+            "\
+# .husky/prepare-commit-msg
+if (exec </dev/tty) 2>/dev/null; then
+    exec </dev/tty
+fi
+gitmoji hook apply \"$1\" \"$2\"
+
+# .husky/commit-msg
+gitmoji hook validate \"$1\"
+",
+        ),
+        HookManager::Lefthook => String::from(
+            "\
+# lefthook.yml
+prepare-commit-msg:
+  commands:
+    gitmoji:
+      interactive: true
+      run: gitmoji hook apply {1} {2}
+commit-msg:
+  commands:
+    gitmoji:
+      run: gitmoji hook validate {1}
+",
+        ),
+    }
+}
+
+async fn read_hook_state(path: &Path) -> Result<HookState> {
+    let contents = match fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(HookState::Missing),
+        Err(err) => return Err(err.into()),
+    };
+    if !is_managed(&contents) {
+        return Ok(HookState::Foreign);
+    }
+
+    let backup = backup_path(path);
+    let chained = fs::try_exists(&backup).await?.then_some(backup);
+    let executable = is_executable(path).await?;
+
+    Ok(HookState::Managed {
+        chained,
+        executable,
+    })
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut filename = path.file_name().unwrap_or_default().to_os_string();
+    filename.push(BACKUP_SUFFIX);
+    path.with_file_name(filename)
+}
+
+// Honor `core.hooksPath`, and the common directory of a worktree
+async fn get_hooks_path() -> Result<PathBuf> {
+    let path = git::get_git_path("hooks").await?;
+    let result = std::env::current_dir()?.join(path);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::check;
+
+    use super::*;
+
+    #[test]
+    fn should_define_backup_path() {
+        let result = backup_path(Path::new("/hooks/commit-msg"));
+
+        check!(result == Path::new("/hooks/commit-msg.pre-gitmoji"));
+    }
+}
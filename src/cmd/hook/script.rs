@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use tokio::fs;
+
+use crate::Result;
+
+const MANAGED_MARKER: &str = "# Managed by gitmoji-rs, use `gitmoji hook remove` to uninstall";
+
+/// The git hooks installed by gitmoji
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GitHook {
+    /// Prompt for the commit message
+    PrepareCommitMsg,
+    /// Validate the commit message
+    CommitMsg,
+}
+
+impl GitHook {
+    pub(super) const ALL: [Self; 2] = [Self::PrepareCommitMsg, Self::CommitMsg];
+
+    pub(super) const fn filename(self) -> &'static str {
+        match self {
+            Self::PrepareCommitMsg => "prepare-commit-msg",
+            Self::CommitMsg => "commit-msg",
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            Self::PrepareCommitMsg => "gitmoji as a commit hook",
+            Self::CommitMsg => "gitmoji commit message validation",
+        }
+    }
+
+    fn command(self, binary: &str) -> String {
+        match self {
+            // Without a TTY (GUI clients, IDEs, CI) a template is written instead of prompting
+            Self::PrepareCommitMsg => format!(
+                "if (exec </dev/tty) 2>/dev/null; then\n    exec </dev/tty\nfi\n\
+                {binary} hook apply \"$1\" \"$2\""
+            ),
+            Self::CommitMsg => format!("{binary} hook validate \"$1\""),
+        }
+    }
+}
+
+/// Generate the hook shell script, calling the `chained` hook first
+pub(super) fn script(hook: GitHook, binary: &Path, chained: Option<&Path>) -> String {
+    let mut lines = vec![
+        String::from("#!/bin/sh"),
+        format!("# {}", hook.description()),
+        String::from(MANAGED_MARKER),
+    ];
+    if let Some(chained) = chained.and_then(Path::file_name) {
+        let chained = chained.to_string_lossy();
+        lines.push(format!("\"$(dirname \"$0\")/{chained}\" \"$@\" || exit $?"));
+    }
+    lines.push(hook.command(&shell_quote(binary)));
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Check the script is generated by gitmoji
+pub(super) fn is_managed(contents: &str) -> bool {
+    contents.lines().any(|line| line == MANAGED_MARKER)
+}
+
+fn shell_quote(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    // The hooks are run by the `sh` bundled with Git for Windows
+    #[cfg(windows)]
+    let path = path.replace('\\', "/");
+    format!("'{}'", path.replace('\'', r"'\''"))
+}
+
+#[cfg(unix)]
+pub(super) async fn set_executable(path: &Path) -> Result<()> {
+    use std::fs::Permissions;
+    use std::os::unix::fs::PermissionsExt;
+
+    const HOOK_PERMISSIONS: u32 = 0o775;
+
+    let perm = Permissions::from_mode(HOOK_PERMISSIONS);
+    fs::set_permissions(path, perm).await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unused_async)]
+pub(super) async fn set_executable(_path: &Path) -> Result<()> {
+    // Git for Windows does not need an executable flag to run the hooks
+    Ok(())
+}
+
+#[cfg(unix)]
+pub(super) async fn is_executable(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path).await?.permissions().mode();
+    Ok(mode & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unused_async)]
+pub(super) async fn is_executable(_path: &Path) -> Result<bool> {
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+
+    use super::*;
+
+    #[test]
+    fn should_generate_script_with_binary_path() {
+        let result = script(
+            GitHook::PrepareCommitMsg,
+            Path::new("/opt/bin/gitmoji"),
+            None,
+        );
+
+        check!(result.starts_with("#!/bin/sh\n"));
+        check!(is_managed(&result));
+        check!(result.ends_with("'/opt/bin/gitmoji' hook apply \"$1\" \"$2\"\n"));
+    }
+
+    #[test]
+    fn should_chain_previous_hook() {
+        let result = script(
+            GitHook::CommitMsg,
+            Path::new("/opt/bin/gitmoji"),
+            Some(Path::new("/hooks/commit-msg.pre-gitmoji")),
+        );
+
+        let expected = "\
+\"$(dirname \"$0\")/commit-msg.pre-gitmoji\" \"$@\" || exit $?
+'/opt/bin/gitmoji' hook validate \"$1\"
+";
+        check!(result.ends_with(expected));
+    }
+
+    #[test]
+    fn should_quote_binary_path() {
+        let result = shell_quote(Path::new("/home/o'neil/bin/gitmoji"));
+
+        check!(result == r"'/home/o'\''neil/bin/gitmoji'");
+    }
+
+    #[test]
+    fn should_not_manage_foreign_script() {
+        check!(!is_managed("#!/bin/sh\necho 'Hello'\n"));
+    }
+}
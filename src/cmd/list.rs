@@ -17,7 +17,7 @@ pub(super) fn print_conventional_commit_emojis(
 ) {
     let blue = Style::new().blue();
     let max_width = conventional_commit_emojis
-        .into_iter()
+        .iter()
         .map(|conventional_commit_emoji| conventional_commit_emoji.r#type().len())
         .max()
         .unwrap_or(25);
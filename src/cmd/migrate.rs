@@ -0,0 +1,233 @@
+use std::fmt::{self, Display};
+
+use serde::de::Error as _;
+use toml_edit::{value, Document};
+
+use crate::{Error, Result};
+
+/// The version of the configuration layout
+pub const CONFIG_VERSION: i64 = 3;
+
+const VERSION_KEY: &str = "version";
+
+/// The keys of the fetched catalog, stored in the cache directory
+pub(super) const CATALOG_KEYS: [&str; 3] =
+    ["last_update", "gitmojis", "conventional_commit_emojis"];
+
+/// A migration step, from a version of the configuration to the next one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStep {
+    from: i64,
+    description: String,
+}
+
+impl MigrationStep {
+    /// The version before the step
+    #[must_use]
+    pub const fn from(&self) -> i64 {
+        self.from
+    }
+}
+
+impl Display for MigrationStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "v{} → v{}: {}",
+            self.from,
+            self.from + 1,
+            self.description
+        )
+    }
+}
+
+/// The migration of a configuration document
+#[derive(Debug, Clone, Default)]
+pub struct Migration {
+    steps: Vec<MigrationStep>,
+    catalog: Document,
+}
+
+impl Migration {
+    /// The applied steps
+    #[must_use]
+    pub fn steps(&self) -> &[MigrationStep] {
+        &self.steps
+    }
+
+    /// The catalog moved out of the configuration
+    #[must_use]
+    pub const fn catalog(&self) -> &Document {
+        &self.catalog
+    }
+
+    /// The version before the migration
+    #[must_use]
+    pub fn previous_version(&self) -> i64 {
+        self.steps
+            .first()
+            .map_or(CONFIG_VERSION, MigrationStep::from)
+    }
+
+    /// Check if the configuration was already up to date
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+// Move the catalog keys from the document to the catalog document
+pub(super) fn split_catalog(document: &mut Document) -> Document {
+    let mut catalog = Document::new();
+    for key in CATALOG_KEYS {
+        if let Some(item) = document.remove(key) {
+            catalog.insert(key, item);
+        }
+    }
+    catalog
+}
+
+// Without a version key, the v2 configuration always has a specification
+fn detect_version(document: &Document) -> Result<i64> {
+    let Some(item) = document.get(VERSION_KEY) else {
+        let version = if document.contains_key("specification") {
+            2
+        } else {
+            1
+        };
+        return Ok(version);
+    };
+    let version = item.as_integer().ok_or_else(|| {
+        toml_edit::de::Error::custom(format!(
+            "invalid {VERSION_KEY} '{item}', expected an integer"
+        ))
+    })?;
+    if !(1..=CONFIG_VERSION).contains(&version) {
+        return Err(Error::UnsupportedConfigVersion(version));
+    }
+    Ok(version)
+}
+
+// The v1 configuration predates the Conventional Emoji Commits specification
+fn add_specification(document: &mut Document) -> String {
+    document.insert("specification", value("Default"));
+    String::from("set the specification to 'Default'")
+}
+
+// The v2 configuration contains the fetched catalog
+fn move_catalog(document: &mut Document, catalog: &mut Document) -> String {
+    *catalog = split_catalog(document);
+    if catalog.is_empty() {
+        return String::from("no catalog to move to the cache directory");
+    }
+    let keys = catalog
+        .iter()
+        .map(|(key, _)| format!("`{key}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("move {keys} to the catalog in the cache directory")
+}
+
+/// Migrate the configuration document to the latest version
+///
+/// # Errors
+/// Fail when the version is invalid, or newer than the supported version
+pub fn migrate(document: &mut Document) -> Result<Migration> {
+    let mut result = Migration::default();
+    let mut version = detect_version(document)?;
+    while version < CONFIG_VERSION {
+        let description = match version {
+            1 => add_specification(document),
+            _ => move_catalog(document, &mut result.catalog),
+        };
+        result.steps.push(MigrationStep {
+            from: version,
+            description,
+        });
+        version += 1;
+    }
+    if !result.is_empty() {
+        document.insert(VERSION_KEY, value(CONFIG_VERSION));
+    }
+    Ok(result)
+}
+
+/// Set the version as the first key of the document
+pub(super) fn with_version(document: &Document) -> Document {
+    let mut result = Document::new();
+    result.insert(VERSION_KEY, value(CONFIG_VERSION));
+    for (key, item) in document.iter() {
+        if key != VERSION_KEY {
+            result.insert(key, item.clone());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+
+    const V1: &str = r#"# My preferences
+auto_add = false
+format = "UseEmoji"
+signed = false
+scope = false
+update_url = "https://gitmoji.dev/api/gitmojis"
+
+[[gitmojis]]
+emoji = "🎨"
+code = ":art:"
+"#;
+
+    #[test]
+    fn should_migrate_v1() {
+        let mut document = V1.parse::<Document>().unwrap();
+
+        let result = migrate(&mut document);
+
+        let_assert!(Ok(migration) = result);
+        check!(migration.previous_version() == 1);
+        check!(migration.steps().len() == 2);
+        check!(migration.catalog().contains_key("gitmojis"));
+        check!(
+            document
+                .get("version")
+                .and_then(toml_edit::Item::as_integer)
+                == Some(CONFIG_VERSION)
+        );
+        check!(
+            document
+                .get("specification")
+                .and_then(toml_edit::Item::as_str)
+                == Some("Default")
+        );
+        check!(!document.contains_key("gitmojis"));
+        check!(document.to_string().starts_with("# My preferences\n"));
+    }
+
+    #[test]
+    fn should_not_migrate_latest() {
+        let mut document = "version = 3\nspecification = \"Default\"\n"
+            .parse::<Document>()
+            .unwrap();
+
+        let result = migrate(&mut document);
+
+        let_assert!(Ok(migration) = result);
+        check!(migration.is_empty());
+        check!(migration.previous_version() == CONFIG_VERSION);
+    }
+
+    #[test]
+    fn should_reject_newer_version() {
+        let mut document = "version = 42\n".parse::<Document>().unwrap();
+
+        let result = migrate(&mut document);
+
+        let_assert!(Err(Error::UnsupportedConfigVersion(42)) = result);
+    }
+}
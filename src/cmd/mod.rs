@@ -1,104 +1,121 @@
-use std::process::exit;
-
-use console::Term;
 use tracing::{info, warn};
 use url::Url;
 
 use crate::git::has_staged_changes;
 use crate::{
-    git, CommitSpecification, EmojiFormat, Error, GitmojiConfig, Result, EXIT_CANNOT_UPDATE,
-    EXIT_NO_CONFIG,
+    git, CommitMessage, CommitSpecification, ConfigTarget, Error, ErrorFormat, FixupKind,
+    GitmojiConfig, Prompter, Result,
 };
 
 mod commit;
 mod config;
+mod diagnostic;
+mod diff;
+mod doctor;
+mod draft;
+mod fixup;
+mod git_args;
 #[cfg(feature = "hook")]
 mod hook;
 mod list;
+mod migrate;
+mod preference;
+mod schema;
+mod scope;
 mod search;
 mod update;
+mod validate;
+mod verify;
 
 pub use self::commit::*;
 pub use self::config::*;
-#[cfg(feature = "hook")]
-pub use self::hook::*;
+use self::diagnostic::config_issues;
+use self::diff::catalog_changes;
+use self::doctor::{diagnose, Status};
+use self::draft::{read_draft, remove_draft, save_draft};
+use self::fixup::{fixup_args, render_subject};
+use self::git_args::GitArgs;
 use self::list::{print_conventional_commit_emojis, print_gitmojis};
+pub use self::migrate::CONFIG_VERSION;
+use self::preference::{get_preference, preference_keys, set_preference, unset_preference};
+use self::schema::{config_schema, local_config_schema};
+use self::scope::resolve_scopes;
 use self::search::filter;
-use self::update::{update_conventional_emoji_commits, update_gitmojis};
+use self::update::fetch_catalog;
+use self::validate::check_subject;
+
+async fn get_config() -> Result<GitmojiConfig> {
+    let config = read_config_or_fail().await.map_err(|err| {
+        warn!("Oops, cannot read config because {err}");
+        err
+    })?;
+    for issue in config_issues(&config) {
+        eprintln!("⚠️  The configuration has an issue, {issue}, run `gitmoji config validate`");
+    }
+    Ok(config)
+}
 
-async fn get_config_or_stop() -> GitmojiConfig {
-    match read_config_or_fail().await {
-        Ok(config) => config,
-        Err(err) => {
-            warn!("Oops, cannot read config because {err}");
-            eprintln!("⚠️  No configuration found, try run `gitmoji init` to fetch a configuration");
-            exit(EXIT_NO_CONFIG)
+async fn fetch_config(config: GitmojiConfig, source: Option<&str>) -> Result<GitmojiConfig> {
+    let url = config
+        .catalog_sources()
+        .iter()
+        .filter(|it| source.map_or(true, |name| it.name() == name))
+        .map(|it| it.location().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    fetch_catalog(config, source).await.map_err(|err| {
+        warn!("Oops, cannot update the config because {err}");
+        match err {
+            Error::UnknownCatalogSource(_) | Error::CatalogVerificationFailed { .. } => err,
+            _ => Error::CannotUpdateConfig {
+                url,
+                source: Box::new(err),
+            },
         }
-    }
+    })
 }
 
-async fn update_config_or_stop(config: GitmojiConfig) -> GitmojiConfig {
-    let url = config.update_url().to_string();
-    match config.specification() {
-        CommitSpecification::Default => match update_gitmojis(config).await {
-            Ok(config) => config,
-            Err(err) => {
-                warn!("Oops, cannot update the config because {err}");
-                eprintln!("⚠️  Configuration not updated, maybe check the update url '{url}'");
-                exit(EXIT_CANNOT_UPDATE)
-            }
-        },
+/// Keep only the gitmojis allowed by the rules for the current changes
+async fn apply_rules(config: &mut GitmojiConfig, all: bool) -> Result<()> {
+    if config.rules().is_empty() {
+        return Ok(());
+    }
+
+    let branch = git::get_current_branch().await?;
+    let paths = git::get_changed_files(all).await?;
+    config.apply_rules(branch.as_deref(), &paths);
+
+    let empty = match config.specification() {
+        CommitSpecification::Default => config.gitmojis().is_empty(),
         CommitSpecification::ConventionalEmojiCommits => {
-            match update_conventional_emoji_commits(config).await {
-                Ok(config) => config,
-                Err(err) => {
-                    println!("{:?}", err);
-                    warn!("Oops, cannot update the config because {err}");
-                    eprintln!("⚠️  Configuration not updated, maybe check the update url '{url}'");
-                    exit(EXIT_CANNOT_UPDATE)
-                }
-            }
+            config.conventional_commit_emojis().is_empty()
         }
+    };
+    if empty {
+        return Err(Error::NoAllowedGitmoji);
     }
-}
 
-#[derive(Debug, Clone)]
-struct CommitTitleDescription {
-    title: String,
-    description: Option<String>,
+    Ok(())
 }
 
-#[tracing::instrument(skip(term))]
-async fn ask_commit_title_description(
+#[tracing::instrument(skip(prompter))]
+async fn ask_commit_message(
     config: &GitmojiConfig,
-    term: &Term,
-) -> Result<CommitTitleDescription> {
-    match config.specification() {
+    scopes: &[String],
+    previous: Option<&CommitMessage>,
+    prompter: &dyn Prompter,
+) -> Result<CommitMessage> {
+    let mut builder = match config.specification() {
         CommitSpecification::Default => {
             let DefaultCommitParams {
                 gitmoji,
                 scope,
                 title,
                 description,
-            } = get_default_commit_params(config, term)?;
-            let gitmoji = match config.format() {
-                EmojiFormat::UseCode => gitmoji.code(),
-                EmojiFormat::UseEmoji => gitmoji.emoji(),
-            };
-
-            let title = match config.specification() {
-                CommitSpecification::Default => scope.map_or_else(
-                    || format!("{gitmoji} {title}"),
-                    |scope| format!("{gitmoji} {scope}{title}"),
-                ),
-                CommitSpecification::ConventionalEmojiCommits => scope.map_or_else(
-                    || format!("{gitmoji} {title}"),
-                    |scope| format!("{gitmoji} {scope}{title}"),
-                ),
-            };
-
-            let result = CommitTitleDescription { title, description };
-            Ok(result)
+            } = get_default_commit_params(config, scopes, previous, prompter)?;
+            CommitMessage::builder_with_gitmoji(&gitmoji, config.format(), title)
+                .scope(scope.unwrap_or_default())
+                .body(description.unwrap_or_default())
         }
         CommitSpecification::ConventionalEmojiCommits => {
             let ConventionalEmojiCommitParams {
@@ -107,57 +124,472 @@ async fn ask_commit_title_description(
                 scope,
                 title,
                 type_name,
-            } = get_conventional_emoji_commit_params(config, term)?;
-            let emoji = match config.format() {
-                EmojiFormat::UseCode => emoji.code(),
-                EmojiFormat::UseEmoji => emoji.emoji(),
-            };
-            let title = match scope {
-                Some(value) if !value.is_empty() => {
-                    format!("{emoji}{type_name}({value}): {title}")
-                }
-                Some(_) | None => {
-                    format!("{emoji}{type_name}: {title}")
-                }
-            };
-            let result = CommitTitleDescription { title, description };
+            } = get_conventional_emoji_commit_params(config, scopes, previous, prompter)?;
+            CommitMessage::builder_with_conventional_emoji(&emoji, config.format(), title)
+                .r#type(type_name)
+                .scope(scope.unwrap_or_default())
+                .body(description.unwrap_or_default())
+        }
+    };
 
-            Ok(result)
+    // The breaking change and the trailers are not prompted, keep them when rewording
+    if let Some(previous) = previous {
+        builder = builder.breaking(previous.breaking());
+        for (key, value) in previous.trailers() {
+            builder = builder.trailer(key, value);
         }
     }
+
+    Ok(builder.build())
+}
+
+/// What to do with the commit message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitMode {
+    /// Run `git commit`
+    Commit,
+    /// Print the message and the git command, without committing
+    DryRun,
+    /// Only print the message
+    Print,
+}
+
+// Quote the arguments when needed, to copy the command in a shell
+fn shell_words(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            let safe = !arg.is_empty()
+                && arg
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || "-_.,:/=@+".contains(ch));
+            if safe {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Commit using Gitmoji
-#[tracing::instrument(skip(term))]
-pub async fn commit(all: bool, amend: bool, term: &Term) -> Result<()> {
-    let config = get_config_or_stop().await;
+#[tracing::instrument(skip(prompter))]
+pub async fn commit(
+    all: bool,
+    amend: bool,
+    mode: CommitMode,
+    resume: bool,
+    git_args: Vec<String>,
+    error_format: ErrorFormat,
+    prompter: &dyn Prompter,
+) -> Result<()> {
+    let mut config = get_config().await?;
 
-    if !amend && !has_staged_changes().await? {
+    // Add before commit
+    let all = all || config.auto_add();
+    let git_args = GitArgs::parse(git_args, all)?;
+
+    // The printed message can be committed later, once the changes are staged
+    if mode != CommitMode::Print
+        && !amend
+        && !git_args.skip_staged_check()
+        && !has_staged_changes().await?
+    {
         eprintln!("No change to commit");
         return Ok(());
     }
 
-    let CommitTitleDescription { title, description } =
-        ask_commit_title_description(&config, term).await?;
+    let draft = if resume { read_draft().await? } else { None };
+    let message = if let Some(message) = draft {
+        eprintln!("Resume the draft '{}'", message.header());
+        message
+    } else {
+        if resume {
+            eprintln!("No draft to resume");
+        }
+        apply_rules(&mut config, all).await?;
+        let scopes = resolve_scopes(&config, all).await?;
+        ask_commit_message(&config, &scopes, None, prompter).await?
+    };
+
+    // Commit
+    let title = message.header();
+    let description = message.description();
+    let signed = config.signed() && !git_args.signing();
+    let args = git::commit_args(
+        all,
+        amend,
+        signed,
+        &title,
+        description.as_deref(),
+        git_args.args(),
+    );
+    match mode {
+        CommitMode::Commit => {}
+        CommitMode::DryRun => {
+            println!("{message}\n");
+            println!("# git {}", shell_words(&args));
+            return Ok(());
+        }
+        CommitMode::Print => {
+            println!("{message}");
+            return Ok(());
+        }
+    }
+    save_draft(&message).await?;
+    let (status, stderr) = git::commit(&args, error_format == ErrorFormat::Json).await?;
+    if !status.success() {
+        eprintln!("The message is saved, use `gitmoji commit --resume` to retry");
+        return Err(Error::FailToCommit {
+            status: status.code(),
+            stderr,
+        });
+    }
+    remove_draft().await?;
+
+    Ok(())
+}
+
+/// Create a fixup commit for a recent commit
+#[tracing::instrument(skip(prompter))]
+pub async fn fixup(
+    kind: FixupKind,
+    count: usize,
+    all: bool,
+    autosquash: bool,
+    git_args: Vec<String>,
+    error_format: ErrorFormat,
+    prompter: &dyn Prompter,
+) -> Result<()> {
+    let mut config = get_config().await?;
 
-    // Add before commit
     let all = all || config.auto_add();
+    let git_args = GitArgs::parse(git_args, all)?;
 
-    // Commit
-    let status = git::commit(all, amend, config.signed(), &title, description.as_deref()).await?;
-    status.success().then_some(()).ok_or(Error::FailToCommit)
+    // An amend can only change the message
+    if kind != FixupKind::Amend && !git_args.skip_staged_check() && !has_staged_changes().await? {
+        eprintln!("No change to commit");
+        return Ok(());
+    }
+
+    let commits = git::list_recent_commits(count).await?;
+    let items = commits
+        .iter()
+        .map(|(id, subject)| {
+            let short_id = id.get(..7).unwrap_or(id);
+            format!("{short_id} {}", render_subject(&config, subject))
+        })
+        .collect::<Vec<_>>();
+    if items.is_empty() {
+        eprintln!("No commit to fix");
+        return Ok(());
+    }
+    let idx = prompter.fuzzy_select("Pick the commit to fix", &items, 0)?;
+    let (target, subject) = &commits[idx];
+    // The rebase would flatten the merges
+    if autosquash && git::has_merges_since(target).await? {
+        return Err(Error::CannotRewriteCommit {
+            commit: target.clone(),
+            reason: String::from("a merge commit follows it, `--autosquash` would flatten it"),
+        });
+    }
+
+    let message = match kind {
+        FixupKind::Fixup => None,
+        FixupKind::Squash => Some(prompter.input("Enter the squash message:", None, true)?),
+        FixupKind::Amend => {
+            apply_rules(&mut config, all).await?;
+            let scopes = resolve_scopes(&config, all).await?;
+            let message = ask_commit_message(&config, &scopes, None, prompter).await?;
+            Some(message.to_string())
+        }
+    };
+
+    let signed = config.signed() && !git_args.signing();
+    let args = fixup_args(
+        kind,
+        (target, subject),
+        all,
+        signed,
+        message.as_deref(),
+        git_args.args(),
+    );
+    let (status, stderr) = git::commit(&args, error_format == ErrorFormat::Json).await?;
+    if !status.success() {
+        return Err(Error::FailToCommit {
+            status: status.code(),
+            stderr,
+        });
+    }
+
+    if autosquash {
+        info!("Autosquash the fixup commit into {target}");
+        git::autosquash(target).await?;
+    }
+
+    Ok(())
+}
+
+/// Reword a commit of the current branch with the prompts, prefilled with its message
+#[tracing::instrument(skip(prompter))]
+pub async fn reword(rev: String, error_format: ErrorFormat, prompter: &dyn Prompter) -> Result<()> {
+    let mut config = get_config().await?;
+
+    let (id, text) = git::get_commit_message(&rev).await?;
+    let cannot_rewrite = |reason: &str| Error::CannotRewriteCommit {
+        commit: rev.clone(),
+        reason: reason.to_string(),
+    };
+    if !git::is_ancestor_of_head(&id).await? {
+        return Err(cannot_rewrite("it's not in the current branch"));
+    }
+    if git::is_published(&id).await? {
+        return Err(cannot_rewrite("it's already published on a remote branch"));
+    }
+    if !git::is_head(&id).await? && git::has_merges_since(&id).await? {
+        return Err(cannot_rewrite("a merge commit follows it"));
+    }
+
+    let previous = text.parse::<CommitMessage>().unwrap_or_else(|_| {
+        // Without a gitmoji, only prefill the title and the body
+        let (title, body) = text.trim().split_once('\n').unwrap_or((text.trim(), ""));
+        CommitMessage::builder("", title).body(body.trim()).build()
+    });
+    let short_id = id.get(..7).unwrap_or(&id);
+    println!("Reword {short_id}");
+    println!("  gitmoji: {}", render_subject(&config, previous.gitmoji()));
+    if let Some(r#type) = previous.r#type() {
+        println!("  type:    {type}");
+    }
+    if let Some(scope) = previous.scope() {
+        println!("  scope:   {scope}");
+    }
+    println!("  title:   {}", previous.title());
+
+    if !config.rules().is_empty() {
+        let branch = git::get_current_branch().await?;
+        let paths = git::get_commit_files(&id).await?;
+        config.apply_rules(branch.as_deref(), &paths);
+    }
+    let scopes = previous
+        .scope()
+        .map(str::to_string)
+        .into_iter()
+        .collect::<Vec<_>>();
+    let message = ask_commit_message(&config, &scopes, Some(&previous), prompter).await?;
+
+    let signed = config.signed();
+    let is_head = git::is_head(&id).await?;
+    let (status, stderr) = if is_head {
+        let args = git::commit_args(
+            false,
+            true,
+            signed,
+            &message.header(),
+            message.description().as_deref(),
+            &[String::from("--only")],
+        );
+        git::commit(&args, error_format == ErrorFormat::Json).await?
+    } else {
+        // Like `git commit --fixup=reword:<id>`, applied by the autosquash
+        let args = fixup_args(
+            FixupKind::Amend,
+            (&id, text.lines().next().unwrap_or_default()),
+            false,
+            signed,
+            Some(&message.to_string()),
+            &[String::from("--only")],
+        );
+        git::commit(&args, error_format == ErrorFormat::Json).await?
+    };
+    if !status.success() {
+        return Err(Error::FailToCommit {
+            status: status.code(),
+            stderr,
+        });
+    }
+    if !is_head {
+        git::autosquash(&id).await?;
+    }
+
+    Ok(())
 }
 
 /// Configure Gitmoji
-#[tracing::instrument(skip(term))]
-pub async fn config(default: bool, term: &Term) -> Result<()> {
+#[tracing::instrument(skip(prompter))]
+pub async fn config(default: bool, prompter: &dyn Prompter) -> Result<()> {
     let config = if default {
         GitmojiConfig::default()
     } else {
-        create_config(term)?
+        create_config(prompter)?
     };
     info!("Loading gitmojis from {}", config.update_url());
-    update_config_or_stop(config).await;
+    let config = fetch_config(config, None).await?;
+    write_config(&config).await?;
+
+    Ok(())
+}
+
+// The document of the targeted configuration file, or of the merged configuration
+async fn get_preferences_document(target: ConfigTarget) -> Result<toml_edit::Document> {
+    if target.is_explicit() {
+        let (_, document) = read_config_document(target.local()).await?;
+        return Ok(document);
+    }
+    let config = get_config().await?;
+    let document = toml_edit::ser::to_document(&config)?;
+    Ok(document)
+}
+
+/// Print a preference
+#[tracing::instrument]
+pub async fn config_get(key: &str, target: ConfigTarget) -> Result<()> {
+    let document = get_preferences_document(target).await?;
+    if let Some(value) = get_preference(&document, key, target.local())? {
+        println!("{value}");
+    }
+    Ok(())
+}
+
+/// Set a preference, keeping the comments of the file
+#[tracing::instrument]
+pub async fn config_set(key: &str, value: &str, target: ConfigTarget) -> Result<()> {
+    let (file, mut document) = edit_config_document(target.local()).await?;
+    set_preference(&mut document, key, value, target.local())?;
+    info!("Update config file {file:?}");
+    tokio::fs::write(file, document.to_string()).await?;
+    Ok(())
+}
+
+/// Remove a preference
+#[tracing::instrument]
+pub async fn config_unset(key: &str, target: ConfigTarget) -> Result<()> {
+    let (file, mut document) = edit_config_document(target.local()).await?;
+    if !unset_preference(&mut document, key, target.local())? {
+        warn!("The preference {key} is not set in {file:?}");
+        return Ok(());
+    }
+    info!("Update config file {file:?}");
+    tokio::fs::write(file, document.to_string()).await?;
+    Ok(())
+}
+
+/// List the preferences
+#[tracing::instrument]
+pub async fn config_list(target: ConfigTarget) -> Result<()> {
+    let document = get_preferences_document(target).await?;
+    for key in preference_keys(target.local()) {
+        if let Some(value) = get_preference(&document, key, target.local())? {
+            println!("{key}={value}");
+        }
+    }
+    Ok(())
+}
+
+/// Open the configuration file in the editor, then check it
+#[tracing::instrument]
+pub async fn config_edit(target: ConfigTarget) -> Result<()> {
+    let (file, _) = edit_config_document(target.local()).await?;
+    if !file.exists() {
+        tokio::fs::write(&file, "").await?;
+    }
+    git::edit_file(&file).await?;
+    check_config_file(&file, target.local()).await
+}
+
+/// Print the JSON schema of the user, or the repository configuration
+#[tracing::instrument]
+pub fn config_print_schema(target: ConfigTarget) -> Result<()> {
+    let schema = if target.local() {
+        local_config_schema()
+    } else {
+        config_schema()
+    };
+    let json = serde_json::to_string_pretty(&schema)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+    println!("{json}");
+    Ok(())
+}
+
+/// Check the configuration files, then the catalog
+#[tracing::instrument]
+pub async fn config_validate() -> Result<()> {
+    let config_file = get_config_file().await?;
+    if !config_file.exists() {
+        return Err(Error::MissingConfigFile);
+    }
+    let files = [
+        (config_file, false),
+        (get_catalog_file().await?, false),
+        (get_local_config_file().await?, true),
+    ];
+
+    let mut count = 0;
+    for (file, local) in files.iter().filter(|(file, _)| file.exists()) {
+        match check_config_file(file, *local).await {
+            Ok(()) => println!("✅ {}", file.display()),
+            Err(err) => {
+                count += 1;
+                println!("❌ {err}");
+            }
+        }
+    }
+    if count == 0 {
+        let config = read_config_or_fail().await?;
+        for issue in config_issues(&config) {
+            count += 1;
+            println!("⚠️  {issue}");
+        }
+    }
+    if count > 0 {
+        return Err(Error::InvalidConfig(count));
+    }
+
+    println!("✅ The configuration is valid");
+    Ok(())
+}
+
+/// Check the environment of gitmoji, and suggest the fixes
+#[tracing::instrument(skip(prompter))]
+pub async fn doctor(offline: bool, prompter: &dyn Prompter) -> Result<()> {
+    let diagnoses = diagnose(offline, prompter).await;
+    for diagnosis in &diagnoses {
+        println!("{diagnosis}");
+    }
+
+    let failures = diagnoses
+        .iter()
+        .filter(|it| it.status() == Status::Fail)
+        .count();
+    if failures > 0 {
+        return Err(Error::DoctorFailed(failures));
+    }
+    Ok(())
+}
+
+/// Migrate the configuration to the latest version
+#[tracing::instrument]
+pub async fn migrate_config(dry_run: bool) -> Result<()> {
+    let (migration, backup) = migrate_config_file(dry_run).await?;
+    if migration.is_empty() {
+        println!("✅ The configuration is up to date (version {CONFIG_VERSION})");
+        return Ok(());
+    }
+    let config_file = get_config_file().await?;
+    println!(
+        "Migrate {} from version {} to {CONFIG_VERSION}",
+        config_file.display(),
+        migration.previous_version()
+    );
+    for step in migration.steps() {
+        println!("  {step}");
+    }
+    if let Some(backup) = backup {
+        println!(
+            "💾 The previous configuration is saved in {}",
+            backup.display()
+        );
+    }
 
     Ok(())
 }
@@ -165,7 +597,7 @@ pub async fn config(default: bool, term: &Term) -> Result<()> {
 /// Search a gitmoji
 #[tracing::instrument]
 pub async fn search(text: &str) -> Result<()> {
-    let config = get_config_or_stop().await;
+    let config = get_config().await?;
     let result = filter(config.gitmojis(), text);
     print_gitmojis(&result);
     Ok(())
@@ -174,7 +606,7 @@ pub async fn search(text: &str) -> Result<()> {
 /// List all Gitmojis
 #[tracing::instrument]
 pub async fn list() -> Result<()> {
-    let config = get_config_or_stop().await;
+    let config = get_config().await?;
     match config.specification() {
         CommitSpecification::ConventionalEmojiCommits => {
             print_conventional_commit_emojis(config.conventional_commit_emojis());
@@ -186,15 +618,47 @@ pub async fn list() -> Result<()> {
     Ok(())
 }
 
-/// Update the configuration with the URL
+/// Update the catalog with the URL, or with the named source, and print the changes
+///
+/// The preferences file is only edited to set the URL, keeping its comments.
+/// With `check`, the configuration is not written, and an available update is an error.
 #[tracing::instrument]
-pub async fn update_config(url: Option<Url>) -> Result<()> {
-    let mut config = read_config_or_default().await;
+pub async fn update_config(url: Option<Url>, source: Option<String>, check: bool) -> Result<()> {
+    // The repository configuration, and the environment overrides, only apply to the current process
+    let mut config = read_user_config_or_default().await?;
+    if let Some(url) = &url {
+        config.set_update_url(url.clone());
+    }
+    let previous = config.clone();
+    let result = fetch_config(config, source.as_deref()).await?;
+
+    let changes = catalog_changes(&previous, &result);
+    if changes.is_empty() {
+        println!("✅ The catalog is up to date");
+    }
+    for change in &changes {
+        println!("{change}");
+    }
+
+    if check {
+        if !changes.is_empty() {
+            return Err(Error::CatalogUpdateAvailable(changes.len()));
+        }
+        return Ok(());
+    }
+    if !get_config_file().await?.exists() {
+        write_config(&result).await?;
+        return Ok(());
+    }
+
+    // The preferences are edited in place, to keep the comments of the file
+    let (file, mut document) = edit_config_document(false).await?;
     if let Some(url) = url {
-        config.set_update_url(url);
+        set_preference(&mut document, "update_url", url.as_str(), false)?;
+        info!("Update config file {file:?}");
+        tokio::fs::write(file, document.to_string()).await?;
     }
-    let result = update_config_or_stop(config).await;
-    print_gitmojis(result.gitmojis());
+    write_catalog(&result).await?;
 
     Ok(())
 }
@@ -202,8 +666,8 @@ pub async fn update_config(url: Option<Url>) -> Result<()> {
 /// Create hook
 #[cfg(feature = "hook")]
 #[tracing::instrument]
-pub async fn create_hook() -> Result<()> {
-    hook::create().await
+pub async fn create_hook(chain: bool) -> Result<()> {
+    hook::create(chain).await
 }
 
 /// Remove hook
@@ -213,41 +677,73 @@ pub async fn remove_hook() -> Result<()> {
     hook::remove().await
 }
 
+/// Print the hook status
+#[cfg(feature = "hook")]
+#[tracing::instrument]
+pub async fn hook_status() -> Result<()> {
+    for status in hook::status().await? {
+        println!("{status}");
+    }
+    Ok(())
+}
+
+/// Print the hook manager configuration
+#[cfg(feature = "hook")]
+pub fn hook_snippet(manager: crate::HookManager) {
+    print!("{}", hook::snippet(manager));
+}
+
 /// Apply hook
 #[cfg(feature = "hook")]
-#[tracing::instrument(skip(term))]
+#[tracing::instrument(skip(prompter))]
 pub async fn apply_hook(
     dest: std::path::PathBuf,
     source: Option<String>,
-    term: &Term,
+    prompter: &dyn Prompter,
 ) -> Result<()> {
-    use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
-
-    let config = get_config_or_stop().await;
-
-    let CommitTitleDescription { title, description } =
-        ask_commit_title_description(&config, term).await?;
+    let mut config = get_config().await?;
+    apply_rules(&mut config, false).await?;
+    let scopes = resolve_scopes(&config, false).await?;
+    hook::apply(&dest, source.as_deref(), &config, &scopes, prompter).await
+}
 
-    info!("Write commit message to {dest:?} with source: {source:?}");
-    let mut file = tokio::fs::OpenOptions::new()
-        .create(true)
-        .read(true)
-        .write(true)
-        .open(dest)
-        .await?;
+/// Validate the commit message from the hook
+#[cfg(feature = "hook")]
+#[tracing::instrument]
+pub async fn validate_hook(dest: std::path::PathBuf) -> Result<()> {
+    let config = get_config().await?;
+    let branch = git::get_current_branch().await?;
+    let paths = git::get_changed_files(false).await?;
+    hook::validate(&dest, &config, branch.as_deref(), &paths).await
+}
 
-    let mut contents = vec![];
-    file.read_to_end(&mut contents).await?;
-    file.seek(std::io::SeekFrom::Start(0)).await?;
+/// Validate the commit messages of a range
+#[tracing::instrument]
+pub async fn validate(range: &str, branch: Option<String>) -> Result<()> {
+    let config = get_config().await?;
+    let branch = match branch {
+        Some(branch) => Some(branch),
+        None => git::get_current_branch().await?,
+    };
 
-    file.write_all(title.as_bytes()).await?;
-    file.write_all(b"\n\n").await?;
+    let mut invalid = 0;
+    for (id, subject) in git::list_commits(range).await? {
+        let paths = git::get_commit_files(&id).await?;
+        let short_id = id.get(..7).unwrap_or(&id);
+        match check_subject(&config, &subject, branch.as_deref(), &paths) {
+            Ok(()) => println!("✅ {short_id} {subject}"),
+            Err(err) => {
+                invalid += 1;
+                println!("❌ {short_id} {subject}: {err}");
+            }
+        }
+    }
 
-    if let Some(description) = description {
-        file.write_all(description.as_bytes()).await?;
-        file.write_all(b"\n").await?;
+    if invalid > 0 {
+        return Err(Error::InvalidCommitMessage(format!(
+            "found {invalid} invalid commit message(s) in '{range}'"
+        )));
     }
-    file.write_all(&contents).await?;
 
     Ok(())
 }
@@ -0,0 +1,222 @@
+use toml_edit::{Document, Item, Value};
+
+use crate::{Error, GitmojiConfig, LocalGitmojiConfig, Result};
+
+/// The preferences, editable with `gitmoji config set`
+const PREFERENCE_KEYS: [&str; 10] = [
+    "auto_add",
+    "specification",
+    "format",
+    "signed",
+    "scope",
+    "update_url",
+    "update_sha256",
+    "update_public_key",
+    "scope_discovery",
+    "allow_other_scope",
+];
+
+/// The preferences not available in the repository configuration
+const GLOBAL_ONLY_KEYS: [&str; 3] = ["update_url", "update_sha256", "update_public_key"];
+
+/// The preference keys of the user, or the repository configuration
+pub(super) fn preference_keys(local: bool) -> impl Iterator<Item = &'static str> {
+    PREFERENCE_KEYS
+        .into_iter()
+        .filter(move |key| !local || !GLOBAL_ONLY_KEYS.contains(key))
+}
+
+fn check_key(key: &str, local: bool) -> Result<()> {
+    if preference_keys(local).any(|it| it == key) {
+        Ok(())
+    } else {
+        Err(Error::UnknownConfigKey(key.to_string()))
+    }
+}
+
+// A TOML value, or a plain string like `UseEmoji` or an URL
+fn parse_value(value: &str) -> Value {
+    let mut result = value
+        .parse::<Value>()
+        .unwrap_or_else(|_| Value::from(value));
+    result.decor_mut().clear();
+    result
+}
+
+/// The preference value, without the quotes of a string
+pub(super) fn get_preference(
+    document: &Document,
+    key: &str,
+    local: bool,
+) -> Result<Option<String>> {
+    check_key(key, local)?;
+    let result = document.get(key).and_then(Item::as_value).map(|value| {
+        value.as_str().map_or_else(
+            || value.clone().decorated("", "").to_string(),
+            ToString::to_string,
+        )
+    });
+    Ok(result)
+}
+
+/// Set the preference, the value is checked against the configuration types
+///
+/// The comments and the formatting of the document are kept
+pub(super) fn set_preference(
+    document: &mut Document,
+    key: &str,
+    value: &str,
+    local: bool,
+) -> Result<()> {
+    check_key(key, local)?;
+    let mut value = parse_value(value);
+    let mut result = document.clone();
+    if let Some(item) = result.get_mut(key) {
+        if let Some(previous) = item.as_value() {
+            *value.decor_mut() = previous.decor().clone();
+        }
+        *item = Item::Value(value);
+    } else {
+        result.insert(key, Item::Value(value));
+    }
+
+    let checked = if local {
+        toml_edit::de::from_document::<LocalGitmojiConfig>(result.clone()).map(drop)
+    } else {
+        toml_edit::de::from_document::<GitmojiConfig>(result.clone()).map(drop)
+    };
+    checked.map_err(|err| Error::InvalidConfigValue {
+        key: key.to_string(),
+        reason: err.message().trim().to_string(),
+    })?;
+    *document = result;
+
+    Ok(())
+}
+
+/// Remove the preference, return `false` if it was not set
+pub(super) fn unset_preference(document: &mut Document, key: &str, local: bool) -> Result<bool> {
+    check_key(key, local)?;
+    Ok(document.remove(key).is_some())
+}
+
+/// The environment variable overriding the preference, like `GITMOJI_FORMAT`
+pub(super) fn env_var_name(key: &str) -> String {
+    format!("GITMOJI_{}", key.to_uppercase())
+}
+
+/// Override the preferences with the environment variables, after the configuration files
+///
+/// # Errors
+/// Fail when a value does not match the type of the preference
+pub(super) fn apply_env_overrides(
+    config: GitmojiConfig,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<GitmojiConfig> {
+    let overrides = preference_keys(false)
+        .filter_map(|key| {
+            let name = env_var_name(key);
+            var(&name).map(|value| (key, name, value))
+        })
+        .collect::<Vec<_>>();
+    if overrides.is_empty() {
+        return Ok(config);
+    }
+
+    let mut document = toml_edit::ser::to_document(&config)?;
+    for (key, name, value) in overrides {
+        set_preference(&mut document, key, &value, false).map_err(|err| match err {
+            Error::InvalidConfigValue { reason, .. } => {
+                Error::InvalidConfigValue { key: name, reason }
+            }
+            err => err,
+        })?;
+    }
+    let result = toml_edit::de::from_document(document)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+
+    const CONFIG: &str = r#"# My preferences
+auto_add = false # no `git add .`
+format = "UseEmoji"
+"#;
+
+    #[test]
+    fn should_set_preference_with_comments() {
+        let mut document = CONFIG.parse::<Document>().unwrap();
+
+        let result = set_preference(&mut document, "auto_add", "true", false);
+
+        let_assert!(Ok(()) = result);
+        check!(
+            document.to_string()
+                == "# My preferences\nauto_add = true # no `git add .`\nformat = \"UseEmoji\"\n"
+        );
+    }
+
+    #[test]
+    fn should_set_plain_string_preference() {
+        let mut document = CONFIG.parse::<Document>().unwrap();
+
+        let result = set_preference(&mut document, "format", "UseCode", false);
+
+        let_assert!(Ok(()) = result);
+        let_assert!(Ok(Some(format)) = get_preference(&document, "format", false));
+        check!(format == "UseCode");
+        check!(document.to_string().contains("format = \"UseCode\"\n"));
+    }
+
+    #[test]
+    fn should_reject_invalid_preference() {
+        let mut document = CONFIG.parse::<Document>().unwrap();
+
+        let result = set_preference(&mut document, "auto_add", "yes", false);
+
+        let_assert!(Err(Error::InvalidConfigValue { key, .. }) = result);
+        check!(key == "auto_add");
+        check!(document.to_string() == CONFIG);
+    }
+
+    #[test]
+    fn should_override_with_env() {
+        let var = |name: &str| match name {
+            "GITMOJI_FORMAT" => Some(String::from("UseCode")),
+            "GITMOJI_SIGNED" => Some(String::from("true")),
+            _ => None,
+        };
+
+        let result = apply_env_overrides(GitmojiConfig::default(), var);
+
+        let_assert!(Ok(config) = result);
+        check!(*config.format() == crate::EmojiFormat::UseCode);
+        check!(config.signed());
+        check!(!config.auto_add());
+    }
+
+    #[test]
+    fn should_reject_invalid_env() {
+        let var = |name: &str| (name == "GITMOJI_AUTO_ADD").then(|| String::from("1"));
+
+        let result = apply_env_overrides(GitmojiConfig::default(), var);
+
+        let_assert!(Err(Error::InvalidConfigValue { key, .. }) = result);
+        check!(key == "GITMOJI_AUTO_ADD");
+    }
+
+    #[test]
+    fn should_reject_unknown_preference() {
+        let mut document = CONFIG.parse::<Document>().unwrap();
+
+        let result = set_preference(&mut document, "update_url", "https://example.com", true);
+
+        let_assert!(Err(Error::UnknownConfigKey(key)) = result);
+        check!(key == "update_url");
+    }
+}
@@ -0,0 +1,106 @@
+use schemars::gen::SchemaSettings;
+use schemars::schema::{InstanceType, Metadata, NumberValidation, RootSchema, SchemaObject};
+use schemars::JsonSchema;
+
+use super::migrate::CONFIG_VERSION;
+use crate::{GitmojiConfig, LocalGitmojiConfig};
+
+// TOML has no null value, so the optional keys are just not required
+fn root_schema<T>() -> RootSchema
+where
+    T: JsonSchema,
+{
+    SchemaSettings::draft07()
+        .with(|settings| settings.option_add_null_type = false)
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
+
+/// The JSON schema of the user configuration, with the `version` key
+pub(super) fn config_schema() -> RootSchema {
+    let mut result = root_schema::<GitmojiConfig>();
+    #[allow(clippy::cast_precision_loss)]
+    let version = SchemaObject {
+        metadata: Some(Box::new(Metadata {
+            description: Some(String::from("The version of the configuration layout")),
+            ..Metadata::default()
+        })),
+        instance_type: Some(InstanceType::Integer.into()),
+        number: Some(Box::new(NumberValidation {
+            minimum: Some(1.0),
+            maximum: Some(CONFIG_VERSION as f64),
+            ..NumberValidation::default()
+        })),
+        ..SchemaObject::default()
+    };
+    result
+        .schema
+        .object()
+        .properties
+        .insert(String::from("version"), version.into());
+    result
+}
+
+/// The JSON schema of the repository configuration, like `.gitmojis.toml`
+pub(super) fn local_config_schema() -> RootSchema {
+    root_schema::<LocalGitmojiConfig>()
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+
+    #[test]
+    fn should_describe_preferences() {
+        let schema = serde_json::to_value(config_schema()).unwrap();
+
+        let properties = &schema["properties"];
+        check!(properties["version"]["maximum"] == 3.0);
+        let_assert!(Some(formats) = schema["definitions"]["EmojiFormat"]["oneOf"].as_array());
+        check!(formats.len() == 2);
+        check!(properties["format"]["description"] == "How the emojis are written in the commits");
+    }
+
+    #[test]
+    fn should_not_have_null_type() {
+        let schema = serde_json::to_value(local_config_schema()).unwrap();
+
+        check!(schema["properties"]["signed"]["type"] == "boolean");
+        check!(schema["properties"].get("update_url").is_none());
+    }
+
+    #[test]
+    fn should_merge_every_local_property() {
+        let local = toml_edit::de::from_str::<LocalGitmojiConfig>(
+            r#"
+auto_add = true
+specification = "ConventionalEmojiCommits"
+format = "UseEmoji"
+signed = true
+scope = true
+scope_discovery = "Directories"
+allow_other_scope = true
+gitmojis = [{ emoji = "🎨", code = ":art:" }]
+conventional_commit_emojis = [{ emoji = "✨", code = ":sparkles:", type = "feat" }]
+rules = [{ paths = ["docs/"], gitmojis = [":art:"] }]
+scope_paths = [{ scope = "docs", paths = ["docs/"] }]
+scopes = [{ name = "api" }]
+"#,
+        )
+        .unwrap();
+        let mut config = GitmojiConfig::default();
+        config.merge(&local);
+
+        let schema = serde_json::to_value(local_config_schema()).unwrap();
+        let_assert!(Some(properties) = schema["properties"].as_object());
+        let local = serde_json::to_value(&local).unwrap();
+        let merged = serde_json::to_value(&config).unwrap();
+        for key in properties.keys() {
+            check!(!local[key].is_null(), "{key}");
+            check!(merged[key] == local[key], "{key}");
+        }
+    }
+}
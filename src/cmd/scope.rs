@@ -0,0 +1,446 @@
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use tokio::fs;
+use tracing::{info, warn};
+
+use crate::{git, GitmojiConfig, Result, ScopeDiscovery};
+
+const CARGO_MANIFEST: &str = "Cargo.toml";
+const NPM_MANIFEST: &str = "package.json";
+const PNPM_WORKSPACE: &str = "pnpm-workspace.yaml";
+const NODE_MODULES: &str = "node_modules";
+
+/// A workspace member, used as a scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Member {
+    name: String,
+    /// The directory, relative to the repository root
+    dir: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CargoManifest {
+    package: Option<CargoPackage>,
+    workspace: Option<CargoWorkspace>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CargoPackage {
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CargoWorkspace {
+    members: Vec<String>,
+    exclude: Vec<String>,
+}
+
+/// Detect the scopes from the changed files
+///
+/// # Errors
+/// Fail if we cannot list the changed files
+pub async fn resolve_scopes(config: &GitmojiConfig, all: bool) -> Result<Vec<String>> {
+    if !config.scope() {
+        return Ok(vec![]);
+    }
+
+    let paths = git::get_changed_files(all).await?;
+    let root = git::get_top_level().await?;
+    let result = detect_scopes(config, &root, &paths).await?;
+    info!("Detected scopes {result:?}");
+
+    Ok(result)
+}
+
+async fn detect_scopes(
+    config: &GitmojiConfig,
+    root: &Path,
+    paths: &[String],
+) -> Result<Vec<String>> {
+    let mut result = vec![];
+    if !config.scope_paths().is_empty() {
+        for path in paths {
+            for scope_paths in config.scope_paths() {
+                if scope_paths.matches(path) {
+                    push_unique(&mut result, scope_paths.scope());
+                }
+            }
+        }
+        return Ok(result);
+    }
+
+    match config.scope_discovery() {
+        ScopeDiscovery::Disabled => {}
+        ScopeDiscovery::Workspace => {
+            let members = workspace_members(root).await?;
+            for path in paths {
+                let member = members
+                    .iter()
+                    .filter(|member| is_in_dir(path, &member.dir))
+                    .max_by_key(|member| member.dir.len());
+                if let Some(member) = member {
+                    push_unique(&mut result, &member.name);
+                }
+            }
+        }
+        ScopeDiscovery::Directories => {
+            for path in paths {
+                if let Some((dir, _)) = path.split_once('/') {
+                    push_unique(&mut result, dir);
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn push_unique(scopes: &mut Vec<String>, scope: &str) {
+    if !scopes.iter().any(|it| it == scope) {
+        scopes.push(scope.to_string());
+    }
+}
+
+fn is_in_dir(path: &str, dir: &str) -> bool {
+    path.strip_prefix(dir)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+async fn workspace_members(root: &Path) -> Result<Vec<Member>> {
+    let mut result = vec![];
+
+    if let Some(manifest) = read_cargo_manifest(&root.join(CARGO_MANIFEST)).await {
+        let workspace = manifest.workspace.unwrap_or_default();
+        let dirs = expand_patterns(root, &workspace.members, CARGO_MANIFEST).await?;
+        // The excluded paths are not glob patterns, but directories
+        for dir in dirs.into_iter().filter(|dir| {
+            !workspace
+                .exclude
+                .iter()
+                .any(|excluded| is_in_dir(dir, normalize_pattern(excluded)))
+        }) {
+            let name = read_cargo_manifest(&root.join(&dir).join(CARGO_MANIFEST))
+                .await
+                .and_then(|manifest| manifest.package)
+                .map(|package| package.name);
+            result.push(member(name, dir));
+        }
+    }
+
+    let mut patterns = read_npm_workspaces(&root.join(NPM_MANIFEST)).await;
+    patterns.extend(read_pnpm_workspaces(&root.join(PNPM_WORKSPACE)).await);
+    for dir in expand_patterns(root, &patterns, NPM_MANIFEST).await? {
+        let name = read_npm_name(&root.join(&dir).join(NPM_MANIFEST)).await;
+        result.push(member(name, dir));
+    }
+
+    Ok(result)
+}
+
+fn member(name: Option<String>, dir: String) -> Member {
+    let name = name
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| dir.rsplit('/').next().unwrap_or_default().to_string());
+    Member { name, dir }
+}
+
+async fn read_cargo_manifest(path: &Path) -> Option<CargoManifest> {
+    let bytes = fs::read(path).await.ok()?;
+    match toml_edit::de::from_slice(&bytes) {
+        Ok(manifest) => Some(manifest),
+        Err(err) => {
+            warn!("Cannot read {path:?} because {err}");
+            None
+        }
+    }
+}
+
+async fn read_npm_manifest(path: &Path) -> Option<serde_json::Value> {
+    let bytes = fs::read(path).await.ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(manifest) => Some(manifest),
+        Err(err) => {
+            warn!("Cannot read {path:?} because {err}");
+            None
+        }
+    }
+}
+
+async fn read_npm_workspaces(path: &Path) -> Vec<String> {
+    let Some(manifest) = read_npm_manifest(path).await else {
+        return vec![];
+    };
+    // Either `"workspaces": [...]` or `"workspaces": { "packages": [...] }`
+    let workspaces = &manifest["workspaces"];
+    let packages = workspaces
+        .as_array()
+        .or_else(|| workspaces["packages"].as_array());
+
+    packages
+        .into_iter()
+        .flatten()
+        .filter_map(|pattern| pattern.as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+async fn read_npm_name(path: &Path) -> Option<String> {
+    let manifest = read_npm_manifest(path).await?;
+    let name = manifest["name"].as_str()?;
+    // Drop the npm organization, `@org/pkg` is `pkg`
+    let name = name.rsplit('/').next().unwrap_or(name);
+    Some(name.to_string())
+}
+
+async fn read_pnpm_workspaces(path: &Path) -> Vec<String> {
+    let Ok(contents) = fs::read_to_string(path).await else {
+        return vec![];
+    };
+
+    // Only the `packages:` list is read, without a full YAML parser
+    contents
+        .lines()
+        .skip_while(|line| line.trim_end() != "packages:")
+        .skip(1)
+        .take_while(|line| line.starts_with(' ') || line.starts_with('-') || line.is_empty())
+        .filter_map(|line| line.trim().strip_prefix('-'))
+        .map(|pattern| pattern.trim().trim_matches(|ch| ch == '\'' || ch == '"'))
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Find the directories with a manifest matching the patterns, a `!pattern` excludes directories
+async fn expand_patterns(root: &Path, patterns: &[String], manifest: &str) -> Result<Vec<String>> {
+    let mut includes = GlobSetBuilder::new();
+    let mut excludes = GlobSetBuilder::new();
+    let mut max_depth = 0;
+    for pattern in patterns {
+        let (builder, pattern) = if let Some(pattern) = pattern.trim().strip_prefix('!') {
+            (&mut excludes, normalize_pattern(pattern))
+        } else {
+            let pattern = normalize_pattern(pattern);
+            // Without `**`, a pattern does not match deeper than its components
+            let depth = if pattern.contains("**") {
+                usize::MAX
+            } else {
+                pattern.split('/').count()
+            };
+            max_depth = max_depth.max(depth);
+            (&mut includes, pattern)
+        };
+        match GlobBuilder::new(pattern).literal_separator(true).build() {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(err) => warn!("Invalid workspace pattern {pattern:?} because {err}"),
+        }
+    }
+    let includes = build_glob_set(&includes);
+    let excludes = build_glob_set(&excludes);
+
+    let mut result = vec![];
+    let mut dirs = vec![(String::new(), 0)];
+    while let Some((dir, depth)) = dirs.pop() {
+        if depth >= max_depth {
+            continue;
+        }
+        let Ok(mut entries) = fs::read_dir(root.join(&dir)).await else {
+            continue;
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !entry.file_type().await?.is_dir() || name.starts_with('.') || name == NODE_MODULES {
+                continue;
+            }
+            let path = join(&dir, &name);
+            if includes.is_match(&path)
+                && !excludes.is_match(&path)
+                && fs::try_exists(root.join(&path).join(manifest)).await?
+            {
+                result.push(path.clone());
+            }
+            dirs.push((path, depth + 1));
+        }
+    }
+    result.sort();
+
+    Ok(result)
+}
+
+fn normalize_pattern(pattern: &str) -> &str {
+    pattern
+        .trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+}
+
+fn build_glob_set(builder: &GlobSetBuilder) -> GlobSet {
+    builder.build().unwrap_or_else(|err| {
+        warn!("Invalid workspace patterns because {err}");
+        GlobSet::empty()
+    })
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+    use crate::{CommitSpecification, EmojiFormat, LocalGitmojiConfig, DEFAULT_URL};
+
+    fn config(local: &str) -> GitmojiConfig {
+        let mut config = GitmojiConfig::new(
+            false,
+            CommitSpecification::Default,
+            EmojiFormat::UseCode,
+            false,
+            true,
+            DEFAULT_URL.parse().unwrap(),
+        );
+        let local_config: LocalGitmojiConfig = toml_edit::de::from_str(local).unwrap();
+        config.merge(&local_config);
+        config
+    }
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(ToString::to_string).collect()
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_detect_cargo_workspace_members() {
+        let root = TempDir::new().unwrap();
+        root.child("Cargo.toml")
+            .write_str(
+                "[workspace]\nmembers = [\"crates/*\", \"xtask\"]\nexclude = [\"crates/legacy\"]\n",
+            )
+            .unwrap();
+        root.child("crates/core/Cargo.toml")
+            .write_str("[package]\nname = \"gitmoji-core\"\n")
+            .unwrap();
+        root.child("crates/cli/Cargo.toml")
+            .write_str("[package]\nname = \"gitmoji-cli\"\n")
+            .unwrap();
+        root.child("crates/legacy/Cargo.toml")
+            .write_str("[package]\nname = \"gitmoji-legacy\"\n")
+            .unwrap();
+        root.child("xtask/Cargo.toml").write_str("").unwrap();
+
+        let changed = paths(&[
+            "crates/legacy/src/lib.rs",
+            "crates/cli/src/main.rs",
+            "crates/core/src/lib.rs",
+            "crates/cli/Cargo.toml",
+            "xtask/src/main.rs",
+            "README.md",
+        ]);
+        let result = detect_scopes(&config(""), root.path(), &changed).await;
+
+        let_assert!(Ok(scopes) = result);
+        check!(scopes == ["gitmoji-cli", "gitmoji-core", "xtask"]);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_detect_npm_and_pnpm_workspace_members() {
+        let root = TempDir::new().unwrap();
+        root.child("package.json")
+            .write_str(r#"{ "workspaces": { "packages": ["apps/*"] } }"#)
+            .unwrap();
+        root.child("pnpm-workspace.yaml")
+            .write_str("packages:\n  - 'libs/*'\n  - '!libs/ignored'\n")
+            .unwrap();
+        root.child("apps/web/package.json")
+            .write_str(r#"{ "name": "@acme/web" }"#)
+            .unwrap();
+        root.child("libs/ui/package.json").write_str("{}").unwrap();
+        root.child("libs/ignored/package.json")
+            .write_str("{}")
+            .unwrap();
+
+        let changed = paths(&[
+            "libs/ignored/index.ts",
+            "libs/ui/index.ts",
+            "apps/web/index.ts",
+        ]);
+        let result = detect_scopes(&config(""), root.path(), &changed).await;
+
+        let_assert!(Ok(scopes) = result);
+        check!(scopes == ["ui", "web"]);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_detect_nested_workspace_members() {
+        let root = TempDir::new().unwrap();
+        root.child("package.json")
+            .write_str(r#"{ "workspaces": ["packages/**"] }"#)
+            .unwrap();
+        root.child("packages/tools/lint/package.json")
+            .write_str(r#"{ "name": "lint" }"#)
+            .unwrap();
+        root.child("packages/tools/lint/node_modules/dep/package.json")
+            .write_str(r#"{ "name": "dep" }"#)
+            .unwrap();
+        root.child("packages/app/package.json")
+            .write_str(r#"{ "name": "app" }"#)
+            .unwrap();
+        root.child("packages/tools/README.md")
+            .write_str("")
+            .unwrap();
+
+        let changed = paths(&[
+            "packages/tools/lint/index.ts",
+            "packages/tools/README.md",
+            "packages/app/src/index.ts",
+        ]);
+        let result = detect_scopes(&config(""), root.path(), &changed).await;
+
+        let_assert!(Ok(scopes) = result);
+        check!(scopes == ["lint", "app"]);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_detect_configured_scopes() {
+        let root = TempDir::new().unwrap();
+        let config = config(
+            r#"
+[[scope_paths]]
+scope = "docs"
+paths = ["docs/", "*.md"]
+"#,
+        );
+
+        let changed = paths(&["README.md", "src/main.rs"]);
+        let result = detect_scopes(&config, root.path(), &changed).await;
+
+        let_assert!(Ok(scopes) = result);
+        check!(scopes == ["docs"]);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_detect_top_level_directories() {
+        let root = TempDir::new().unwrap();
+        let config = config("scope_discovery = \"Directories\"");
+
+        let changed = paths(&["api/main.go", "web/index.ts", "api/go.mod", "README.md"]);
+        let result = detect_scopes(&config, root.path(), &changed).await;
+
+        let_assert!(Ok(scopes) = result);
+        check!(scopes == ["api", "web"]);
+    }
+}
@@ -1,11 +1,15 @@
-use std::collections::HashMap;
-
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use tokio::fs;
 use tracing::{debug, info};
 
-use super::write_config;
+use super::verify::{check_minisign, check_sha256};
 use crate::model::GitmojiConfig;
-use crate::{ConventionalEmojiCommit, Gitmoji, Result};
+use crate::{
+    git, CatalogKind, CatalogLocation, CatalogSource, ConventionalEmojiCommit, Error, Gitmoji,
+    Result,
+};
 
 #[derive(Debug, Clone, Default, Deserialize)]
 struct GetGitmojis {
@@ -14,38 +18,179 @@ struct GetGitmojis {
 
 #[derive(Debug, Clone, Default, Deserialize)]
 struct GetConventionalEmojiCommitsTypes {
-    types: HashMap<String, ConventionalEmojiCommit>,
+    types: IndexMap<String, ConventionalEmojiCommit>,
+}
+
+async fn read_location(location: &CatalogLocation) -> Result<Vec<u8>> {
+    let result = match location {
+        CatalogLocation::Url { url } => reqwest::get(url.clone())
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec(),
+        CatalogLocation::Path { path } => fs::read(path).await?,
+        CatalogLocation::Git { git, file, rev } => git::read_remote_file(git, rev.as_deref(), file)
+            .await?
+            .into_bytes(),
+    };
+    Ok(result)
 }
 
-async fn get_gitmojis(url: &str) -> Result<GetGitmojis> {
-    info!("Update gitmojis with {url}");
-    let result = reqwest::get(url).await?.json::<GetGitmojis>().await?;
+/// Check the catalog with the pinned digest and signature of the source, before using it
+async fn verify_catalog(source: &CatalogSource, bytes: &[u8]) -> Result<()> {
+    let failed = |reason| Error::CatalogVerificationFailed {
+        name: source.name().to_string(),
+        reason,
+    };
+    if let Some(sha256) = source.sha256() {
+        check_sha256(sha256, bytes).map_err(failed)?;
+    }
+    if let Some(public_key) = source.public_key() {
+        let location = source.location().signature();
+        debug!("Read the signature {location}");
+        let signature = read_location(&location).await.map_err(|err| {
+            failed(format!(
+                "cannot read the signature '{location}' because {err}"
+            ))
+        })?;
+        let signature = String::from_utf8_lossy(&signature);
+        check_minisign(public_key, &signature, bytes).map_err(failed)?;
+    }
+    Ok(())
+}
+
+async fn get_catalog<T>(source: &CatalogSource) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let location = source.location();
+    let bytes = read_location(location).await?;
+    verify_catalog(source, &bytes).await?;
+    serde_json::from_slice(&bytes)
+        .map_err(|err| Error::InvalidCatalog(format!("cannot read '{location}' because {err}")))
+}
+
+async fn get_gitmojis(source: &CatalogSource) -> Result<GetGitmojis> {
+    info!("Update gitmojis with {}", source.location());
+    let result = get_catalog::<GetGitmojis>(source).await?;
     debug!("Found {} gitmojis", result.gitmojis.len());
     Ok(result)
 }
 
-async fn get_conventional_commit_emojis(url: &str) -> Result<GetConventionalEmojiCommitsTypes> {
-    info!("Update conventional emoji commits with {url}");
-    let result = reqwest::get(url)
-        .await?
-        .json::<GetConventionalEmojiCommitsTypes>()
-        .await?;
+async fn get_conventional_commit_emojis(
+    source: &CatalogSource,
+) -> Result<GetConventionalEmojiCommitsTypes> {
+    info!(
+        "Update conventional emoji commits with {}",
+        source.location()
+    );
+    let result = get_catalog::<GetConventionalEmojiCommitsTypes>(source).await?;
     debug!("Found {} conventional emoji commits", result.types.len());
     Ok(result)
 }
 
-pub async fn update_gitmojis(mut config: GitmojiConfig) -> Result<GitmojiConfig> {
-    let result = get_gitmojis(config.update_url()).await?;
-    config.set_gitmojis(result.gitmojis);
-    write_config(&config).await?;
+/// Merge the entries of the sources, the first source wins on conflict
+fn merge_entries<T, K>(entries: Vec<Vec<T>>, key: impl Fn(&T) -> K) -> Vec<T>
+where
+    K: PartialEq,
+{
+    let mut result = Vec::<T>::new();
+    for entry in entries.into_iter().flatten() {
+        if !result.iter().any(|it| key(it) == key(&entry)) {
+            result.push(entry);
+        }
+    }
+    result
+}
 
-    Ok(config)
+// The current entries of the source, or without source, when it is not refreshed
+fn cached<T>(entries: &[T], name: Option<&str>, source: impl Fn(&T) -> Option<&str>) -> Vec<T>
+where
+    T: Clone,
+{
+    entries
+        .iter()
+        .filter(|it| source(it) == name)
+        .cloned()
+        .collect()
 }
 
-pub async fn update_conventional_emoji_commits(mut config: GitmojiConfig) -> Result<GitmojiConfig> {
-    let result = get_conventional_commit_emojis(config.update_url()).await?;
-    config.set_conventional_commit_emojis(result.types);
-    write_config(&config).await?;
+/// Fetch the catalogs from the sources, or only from the named source, without writing them
+///
+/// The entries of the sources are merged by priority, with a named source the other sources
+/// keep their current entries, and the entries without source have the lowest priority.
+pub async fn fetch_catalog(
+    mut config: GitmojiConfig,
+    source: Option<&str>,
+) -> Result<GitmojiConfig> {
+    let mut sources = config.catalog_sources();
+    if let Some(name) = source {
+        if !sources.iter().any(|it| it.name() == name) {
+            return Err(Error::UnknownCatalogSource(name.to_string()));
+        }
+    }
+    // The stable sort keeps the configuration order for the same priority
+    sources.sort_by_key(|it| std::cmp::Reverse(it.priority()));
+    let refreshed = |it: &CatalogSource| source.map_or(true, |name| it.name() == name);
+
+    let (gitmoji_sources, conventional_sources): (Vec<CatalogSource>, Vec<CatalogSource>) = sources
+        .into_iter()
+        .partition(|it| it.kind() == CatalogKind::Gitmojis);
+
+    if gitmoji_sources.iter().any(refreshed) {
+        let mut entries = vec![];
+        for catalog_source in &gitmoji_sources {
+            let name = catalog_source.name();
+            let gitmojis = if refreshed(catalog_source) {
+                let mut gitmojis = get_gitmojis(catalog_source).await?.gitmojis;
+                for gitmoji in &mut gitmojis {
+                    gitmoji.set_source(Some(name.to_string()));
+                }
+                gitmojis
+            } else {
+                cached(config.gitmojis(), Some(name), Gitmoji::source)
+            };
+            entries.push(gitmojis);
+        }
+        if source.is_some() {
+            entries.push(cached(config.gitmojis(), None, Gitmoji::source));
+        }
+        config.set_gitmojis(merge_entries(entries, |it| it.code().to_string()));
+    }
+
+    if conventional_sources.iter().any(refreshed) {
+        let with_type = |emoji: ConventionalEmojiCommit| (emoji.r#type().to_string(), emoji);
+        let mut entries = vec![];
+        for catalog_source in &conventional_sources {
+            let name = catalog_source.name();
+            let types = if refreshed(catalog_source) {
+                get_conventional_commit_emojis(catalog_source)
+                    .await?
+                    .types
+                    .into_iter()
+                    .map(|(r#type, mut emoji)| {
+                        emoji.set_source(Some(name.to_string()));
+                        (r#type, emoji)
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                let current = config.conventional_commit_emojis();
+                cached(current, Some(name), ConventionalEmojiCommit::source)
+                    .into_iter()
+                    .map(with_type)
+                    .collect()
+            };
+            entries.push(types);
+        }
+        if source.is_some() {
+            let current = config.conventional_commit_emojis();
+            let types = cached(current, None, ConventionalEmojiCommit::source);
+            entries.push(types.into_iter().map(with_type).collect());
+        }
+        let types = merge_entries(entries, |(r#type, _)| r#type.clone());
+        config.set_conventional_commit_emojis(types.into_iter().collect());
+    }
 
     Ok(config)
 }
@@ -59,6 +204,13 @@ mod tests {
 
     use super::*;
 
+    fn source(url: &str) -> CatalogSource {
+        let location = CatalogLocation::Url {
+            url: url.parse().unwrap(),
+        };
+        CatalogSource::new(String::from("test"), CatalogKind::Gitmojis, 0, location)
+    }
+
     #[test_log::test(tokio::test)]
     async fn should_get_gitmojis() {
         let mock_server = MockServer::start().await;
@@ -81,7 +233,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let result = get_gitmojis(&mock_server.uri()).await.unwrap();
+        let result = get_gitmojis(&source(&mock_server.uri())).await.unwrap();
 
         check!(result.gitmojis.len() == 1);
     }
@@ -99,7 +251,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let result = get_gitmojis(&mock_server.uri()).await;
+        let result = get_gitmojis(&source(&mock_server.uri())).await;
 
         let_assert!(Err(_) = result);
     }
@@ -112,8 +264,159 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let result = get_gitmojis(&mock_server.uri()).await;
+        let result = get_gitmojis(&source(&mock_server.uri())).await;
 
         let_assert!(Err(_) = result);
     }
+
+    fn write_catalog(path: &std::path::Path, gitmojis: &[(&str, &str, &str)]) {
+        let gitmojis = gitmojis
+            .iter()
+            .map(|(emoji, code, description)| {
+                format!(r#"{{"emoji": "{emoji}", "code": "{code}", "description": "{description}", "name": null}}"#)
+            })
+            .collect::<Vec<_>>();
+        let json = format!(r#"{{"gitmojis": [{}]}}"#, gitmojis.join(", "));
+        std::fs::write(path, json).unwrap();
+    }
+
+    fn descriptions(config: &GitmojiConfig) -> Vec<(String, String)> {
+        config
+            .gitmojis()
+            .iter()
+            .map(|it| {
+                let description = it.description().unwrap_or_default().to_string();
+                (description, it.source().unwrap_or_default().to_string())
+            })
+            .collect()
+    }
+
+    // The upstream source with the lowest priority is declared first
+    fn local_sources(dir: &assert_fs::TempDir) -> GitmojiConfig {
+        let mut config = GitmojiConfig::default();
+        let path = |name: &str| CatalogLocation::Path {
+            path: dir.path().join(format!("{name}.json")),
+        };
+        config.set_sources(vec![
+            CatalogSource::new(
+                String::from("upstream"),
+                CatalogKind::Gitmojis,
+                0,
+                path("upstream"),
+            ),
+            CatalogSource::new(
+                String::from("team"),
+                CatalogKind::Gitmojis,
+                10,
+                path("team"),
+            ),
+        ]);
+        config
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_merge_sources_by_priority() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        write_catalog(
+            &dir.path().join("upstream.json"),
+            &[
+                ("🎨", ":art:", "Improve structure."),
+                ("⚡️", ":zap:", "Improve performance."),
+            ],
+        );
+        write_catalog(&dir.path().join("team.json"), &[("⚡️", ":zap:", "Faster!")]);
+
+        let result = fetch_catalog(local_sources(&dir), None).await;
+
+        let_assert!(Ok(config) = result);
+        check!(
+            descriptions(&config)
+                == [
+                    (String::from("Faster!"), String::from("team")),
+                    (String::from("Improve structure."), String::from("upstream")),
+                ]
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_keep_priority_when_refreshing_one_source() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let upstream = dir.path().join("upstream.json");
+        let team = dir.path().join("team.json");
+        write_catalog(&upstream, &[("⚡️", ":zap:", "Improve performance.")]);
+        write_catalog(&team, &[("⚡️", ":zap:", "Faster!")]);
+        let config = fetch_catalog(local_sources(&dir), None).await.unwrap();
+        write_catalog(
+            &upstream,
+            &[
+                ("⚡️", ":zap:", "Improve the performance."),
+                ("🐛", ":bug:", "Fix a bug."),
+            ],
+        );
+        write_catalog(&team, &[("⚡️", ":zap:", "Not refreshed")]);
+
+        let result = fetch_catalog(config, Some("upstream")).await;
+
+        let_assert!(Ok(config) = result);
+        check!(
+            descriptions(&config)
+                == [
+                    (String::from("Faster!"), String::from("team")),
+                    (String::from("Fix a bug."), String::from("upstream")),
+                ]
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_keep_conventional_types_order() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("types.json");
+        std::fs::write(
+            &path,
+            r#"{"types": {
+                "feat": {"emoji": "✨", "code": ":sparkles:", "type": "feat", "description": "Feature"},
+                "fix": {"emoji": "🐛", "code": ":bug:", "type": "fix", "description": "Fix"},
+                "docs": {"emoji": "📝", "code": ":memo:", "type": "docs", "description": "Docs"},
+                "chore": {"emoji": "🔧", "code": ":wrench:", "type": "chore", "description": "Chore"}
+            }}"#,
+        )
+        .unwrap();
+        let mut config = GitmojiConfig::default();
+        config.set_sources(vec![CatalogSource::new(
+            String::from("types"),
+            CatalogKind::ConventionalEmojiCommits,
+            0,
+            CatalogLocation::Path { path },
+        )]);
+
+        let result = fetch_catalog(config, None).await;
+
+        let_assert!(Ok(config) = result);
+        let types = config
+            .conventional_commit_emojis()
+            .iter()
+            .map(ConventionalEmojiCommit::r#type)
+            .collect::<Vec<_>>();
+        check!(types == ["feat", "fix", "docs", "chore"]);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn should_reject_catalog_not_matching_sha256() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(r#"{"gitmojis": []}"#, "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+        let mut source = source(&mock_server.uri());
+        source.set_sha256(Some(String::from(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        )));
+
+        let result = get_gitmojis(&source).await;
+
+        let_assert!(Err(Error::CatalogVerificationFailed { name, .. }) = result);
+        check!(name == "test");
+    }
 }
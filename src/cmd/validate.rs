@@ -0,0 +1,167 @@
+use crate::message::parse_header;
+use crate::{AllowedScope, CommitSpecification, Error, GitmojiConfig, Result};
+
+/// Check the subject starts with a gitmoji allowed by the rules, and uses an allowed scope
+pub(super) fn check_subject(
+    config: &GitmojiConfig,
+    subject: &str,
+    branch: Option<&str>,
+    paths: &[String],
+) -> Result<()> {
+    let subject = strip_autosquash_prefix(subject);
+    let Some(prefix) = config.find_gitmoji_prefix(subject) else {
+        return Err(Error::InvalidCommitMessage(format!(
+            "'{subject}' does not start with a gitmoji"
+        )));
+    };
+
+    let mut allowed = config.clone();
+    allowed.apply_rules(branch, paths);
+    if allowed.find_gitmoji_prefix(subject).is_none() {
+        let codes = match allowed.specification() {
+            CommitSpecification::Default => allowed
+                .gitmojis()
+                .iter()
+                .map(|gitmoji| gitmoji.code().to_string())
+                .collect::<Vec<_>>(),
+            CommitSpecification::ConventionalEmojiCommits => allowed
+                .conventional_commit_emojis()
+                .iter()
+                .map(|emoji| emoji.code().to_string())
+                .collect(),
+        };
+        return Err(Error::InvalidCommitMessage(format!(
+            "'{prefix}' is not allowed by the rules, expected one of {}",
+            codes.join(", ")
+        )));
+    }
+
+    if let Some(scope) = parse_header(&subject[prefix.len()..]).scope {
+        if !config.is_scope_allowed(scope) {
+            let names = config
+                .scopes()
+                .iter()
+                .map(AllowedScope::name)
+                .collect::<Vec<_>>();
+            return Err(Error::InvalidCommitMessage(format!(
+                "'{scope}' is not an allowed scope, expected one of {}",
+                names.join(", ")
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// The prefixes of the commits applied by `git rebase --autosquash`
+const AUTOSQUASH_PREFIXES: [&str; 3] = ["fixup! ", "squash! ", "amend! "];
+
+// The fixup of a fixup is prefixed several times
+fn strip_autosquash_prefix(subject: &str) -> &str {
+    let mut result = subject;
+    while let Some(rest) = AUTOSQUASH_PREFIXES
+        .iter()
+        .find_map(|prefix| result.strip_prefix(prefix))
+    {
+        result = rest;
+    }
+    result
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+    use crate::{Gitmoji, LocalGitmojiConfig};
+
+    fn config() -> GitmojiConfig {
+        let mut config = GitmojiConfig::default();
+        config.set_gitmojis(vec![
+            Gitmoji::new(
+                String::from("📝"),
+                String::from(":memo:"),
+                Some(String::from("memo")),
+                None,
+            ),
+            Gitmoji::new(
+                String::from("⚡️"),
+                String::from(":zap:"),
+                Some(String::from("zap")),
+                None,
+            ),
+        ]);
+        let local_config: LocalGitmojiConfig = toml_edit::de::from_str(
+            r#"
+[[rules]]
+paths = ["docs/"]
+gitmojis = ["📝"]
+
+[[scopes]]
+name = "api"
+description = "The REST API"
+
+[[scopes]]
+name = "web"
+"#,
+        )
+        .unwrap();
+        config.merge(&local_config);
+        config
+    }
+
+    #[test]
+    fn should_accept_allowed_gitmoji() {
+        let config = config();
+        let paths = vec![String::from("docs/index.md")];
+
+        check!(check_subject(&config, ":memo: Add docs", None, &paths).is_ok());
+        check!(check_subject(&config, ":zap: Faster", None, &[]).is_ok());
+    }
+
+    #[test]
+    fn should_reject_gitmoji_not_allowed_by_rules() {
+        let config = config();
+        let paths = vec![String::from("src/lib.rs"), String::from("docs/index.md")];
+
+        let result = check_subject(&config, ":zap: Faster", None, &paths);
+
+        let_assert!(Err(Error::InvalidCommitMessage(message)) = result);
+        check!(message.contains(":memo:"));
+    }
+
+    #[test]
+    fn should_accept_autosquash_commits() {
+        let config = config();
+
+        check!(check_subject(&config, "fixup! :zap: Faster", None, &[]).is_ok());
+        check!(check_subject(&config, "amend! squash! :zap: Faster", None, &[]).is_ok());
+        check!(check_subject(&config, "fixup! Faster", None, &[]).is_err());
+    }
+
+    #[test]
+    fn should_reject_missing_gitmoji() {
+        let result = check_subject(&config(), "Faster", None, &[]);
+
+        let_assert!(Err(Error::InvalidCommitMessage(_)) = result);
+    }
+
+    #[test]
+    fn should_accept_allowed_scopes() {
+        let config = config();
+
+        check!(check_subject(&config, ":zap: (api): Faster", None, &[]).is_ok());
+        check!(check_subject(&config, ":zap: (api,web): Faster", None, &[]).is_ok());
+        check!(check_subject(&config, ":zap: Faster (really)", None, &[]).is_ok());
+        check!(check_subject(&config, ":zap:perf(web)!: Faster", None, &[]).is_ok());
+    }
+
+    #[test]
+    fn should_reject_unknown_scope() {
+        let result = check_subject(&config(), ":zap: (cli): Faster", None, &[]);
+
+        let_assert!(Err(Error::InvalidCommitMessage(message)) = result);
+        check!(message.contains("'cli' is not an allowed scope"));
+    }
+}
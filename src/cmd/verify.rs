@@ -0,0 +1,145 @@
+use std::fmt::Write;
+
+use minisign_verify::{PublicKey, Signature};
+use ring::digest::{digest, SHA256};
+
+/// Check the SHA-256 digest of the content, given as an hexadecimal string
+pub(super) fn check_sha256(expected: &str, content: &[u8]) -> Result<(), String> {
+    let actual = to_hex(digest(&SHA256, content).as_ref());
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "the SHA-256 digest is {actual}, expected {expected}"
+        ))
+    }
+}
+
+/// Check the minisign signature of the content, with the minisign public key
+///
+/// The public key is the base64 line of the `minisign.pub` file,
+/// the signature is the content of the `.minisig` file.
+pub(super) fn check_minisign(
+    public_key: &str,
+    signature: &str,
+    content: &[u8],
+) -> Result<(), String> {
+    let public_key = PublicKey::from_base64(public_key.trim())
+        .map_err(|err| format!("invalid public key, {err}"))?;
+    let signature =
+        Signature::decode(signature.trim()).map_err(|err| format!("invalid signature, {err}"))?;
+    // The legacy signatures are still made by `minisign -S -l`
+    public_key
+        .verify(content, &signature, true)
+        .map_err(|err| err.to_string())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut result, byte| {
+        let _ = write!(result, "{byte:02x}");
+        result
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use blake2::{Blake2b512, Digest};
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    use super::*;
+
+    const KEY_ID: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+    /// The minisign algorithm signing the content
+    const ALGORITHM_LEGACY: &[u8] = b"Ed";
+    /// The minisign algorithm signing the BLAKE2b-512 digest of the content
+    const ALGORITHM_PREHASHED: &[u8] = b"ED";
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    fn public_key(key_pair: &Ed25519KeyPair) -> String {
+        STANDARD.encode([b"Ed", &KEY_ID[..], key_pair.public_key().as_ref()].concat())
+    }
+
+    fn sign(key_pair: &Ed25519KeyPair, algorithm: &[u8], content: &[u8]) -> String {
+        let message = if algorithm == ALGORITHM_PREHASHED {
+            Blake2b512::digest(content).to_vec()
+        } else {
+            content.to_vec()
+        };
+        let signature = key_pair.sign(&message);
+        let trusted_comment = "timestamp:1700000000\tfile:gitmojis.json";
+        let global_signature =
+            key_pair.sign(&[signature.as_ref(), trusted_comment.as_bytes()].concat());
+        format!(
+            "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {trusted_comment}\n{}\n",
+            STANDARD.encode([algorithm, &KEY_ID[..], signature.as_ref()].concat()),
+            STANDARD.encode(global_signature.as_ref()),
+        )
+    }
+
+    #[test]
+    fn should_check_sha256() {
+        let expected = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+
+        check!(check_sha256(expected, b"abc").is_ok());
+        let_assert!(Err(reason) = check_sha256(expected, b"abd"));
+        check!(reason.contains("expected"));
+    }
+
+    #[test]
+    fn should_check_minisign_signatures() {
+        let key_pair = key_pair();
+        let public_key = public_key(&key_pair);
+        let content = br#"{"gitmojis": []}"#;
+
+        for algorithm in [ALGORITHM_LEGACY, ALGORITHM_PREHASHED] {
+            let signature = sign(&key_pair, algorithm, content);
+
+            check!(check_minisign(&public_key, &signature, content).is_ok());
+            check!(check_minisign(&public_key, &signature, b"{}").is_err());
+        }
+    }
+
+    #[test]
+    fn should_check_minisign_file() {
+        // Made by `minisign -S -m test`
+        let public_key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+        let signature = "\
+untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==
+";
+
+        check!(check_minisign(public_key, signature, b"test").is_ok());
+        check!(check_minisign(public_key, signature, b"tests").is_err());
+    }
+
+    #[test]
+    fn should_reject_tampered_trusted_comment() {
+        let key_pair = key_pair();
+        let signature = sign(&key_pair, ALGORITHM_PREHASHED, b"{}")
+            .replace("file:gitmojis.json", "file:other.json");
+
+        let result = check_minisign(&public_key(&key_pair), &signature, b"{}");
+
+        let_assert!(Err(_) = result);
+    }
+
+    #[test]
+    fn should_reject_other_key() {
+        let signature = sign(&key_pair(), ALGORITHM_PREHASHED, b"{}");
+
+        let result = check_minisign(&public_key(&key_pair()), &signature, b"{}");
+
+        let_assert!(Err(_) = result);
+    }
+}
@@ -1,4 +1,9 @@
 use crate::git::GitCommandError;
+use crate::{
+    ErrorFormat, EXIT_CANNOT_UPDATE, EXIT_DOCTOR, EXIT_FAIL_TO_COMMIT, EXIT_GIT_COMMAND, EXIT_HOOK,
+    EXIT_INVALID_ARGUMENTS, EXIT_INVALID_COMMIT_MESSAGE, EXIT_INVALID_CONFIG, EXIT_IO,
+    EXIT_NO_CONFIG, EXIT_PROMPT, EXIT_UPDATE_AVAILABLE,
+};
 
 #[derive(Debug, thiserror::Error)]
 /// Gitmojis errors
@@ -12,14 +17,112 @@ pub enum Error {
     /// Cannot get the project config file
     CannotGetProjectConfigFile(String),
 
-    #[error("Fail to commit")]
-    /// Cannot commit
-    FailToCommit,
+    #[error("Cannot update the configuration from '{url}' because {source}")]
+    /// Cannot update the gitmojis with the update URL
+    CannotUpdateConfig {
+        /// The update URL
+        url: String,
+        /// The source error
+        source: Box<Error>,
+    },
 
-    #[error("Missing the configuration file, to create it use `gitmoji config`")]
+    #[error("Invalid catalog, {0}")]
+    /// The catalog of a source cannot be read
+    InvalidCatalog(String),
+
+    #[error("Cannot verify the catalog of the source '{name}', {reason}")]
+    /// The catalog does not match the pinned digest or signature
+    CatalogVerificationFailed {
+        /// The source name
+        name: String,
+        /// Why the verification failed
+        reason: String,
+    },
+
+    #[error("An update of the catalog is available, with {0} change(s)")]
+    /// The catalog is outdated, with `gitmoji update --check`
+    CatalogUpdateAvailable(usize),
+
+    #[error("Unknown catalog source '{0}'")]
+    /// The catalog source is not configured
+    UnknownCatalogSource(String),
+
+    #[error("Cannot rewrite the commit '{commit}' because {reason}")]
+    /// The commit cannot be rewritten
+    CannotRewriteCommit {
+        /// The commit revision
+        commit: String,
+        /// Why the commit cannot be rewritten
+        reason: String,
+    },
+
+    #[error("Fail to commit, git exited with {}{}", display_status(*.status), display_stderr(.stderr))]
+    /// The `git commit` command failed
+    FailToCommit {
+        /// The git exit code, if any
+        status: Option<i32>,
+        /// The git error output
+        stderr: String,
+    },
+
+    #[error("Invalid git arguments, {0}")]
+    /// The extra git arguments conflict with the gitmoji options
+    InvalidGitArguments(String),
+
+    #[error("Invalid commit message, {0}")]
+    /// The commit message does not follow the gitmoji convention
+    InvalidCommitMessage(String),
+
+    #[error("No gitmoji is allowed by the rules for these changes")]
+    /// The rules do not allow any gitmoji
+    NoAllowedGitmoji,
+
+    #[error("A hook already exists in {0:?}, use `gitmoji hook add --chain` to keep it")]
+    /// A hook not managed by gitmoji already exists
+    HookAlreadyExists(std::path::PathBuf),
+
+    #[error("Missing the configuration file, to create it use `gitmoji init`")]
     /// Configuration file not found
     MissingConfigFile,
 
+    #[error("Invalid configuration {}{}, {reason}", .file.display(), display_position(*.line, *.column))]
+    /// The configuration file cannot be read
+    InvalidConfigFile {
+        /// The configuration file
+        file: std::path::PathBuf,
+        /// The line of the error, starting at 1
+        line: Option<usize>,
+        /// The column of the error, starting at 1
+        column: Option<usize>,
+        /// Why the file is invalid
+        reason: String,
+    },
+
+    #[error("The configuration has {0} issue(s)")]
+    /// The configuration has issues, with `gitmoji config validate`
+    InvalidConfig(usize),
+
+    #[error("Unknown configuration key '{0}'")]
+    /// The key is not a preference of the configuration
+    UnknownConfigKey(String),
+
+    #[error("Invalid value for '{key}', {reason}")]
+    /// The value does not match the type of the preference
+    InvalidConfigValue {
+        /// The preference key
+        key: String,
+        /// Why the value is invalid
+        reason: String,
+    },
+
+    #[error("Unsupported configuration version {0}, upgrade gitmoji to read it")]
+    /// The configuration was written by a newer version
+    UnsupportedConfigVersion(i64),
+
+    #[error("Found {0} problem(s), see the suggested fixes")]
+    /// A check of `gitmoji doctor` failed
+    DoctorFailed(usize),
+
     #[error(transparent)]
     /// I/O error
     IoError(#[from] std::io::Error),
@@ -43,7 +146,172 @@ pub enum Error {
     /// A Dialoguer error
     #[error(transparent)]
     DialoguerError(#[from] dialoguer::Error),
+
+    /// A prompt cannot be answered
+    #[error("Cannot prompt, {0}")]
+    PromptError(String),
+}
+
+impl Error {
+    /// The process exit code for the error
+    ///
+    /// | Code | Errors                                       |
+    /// |------|----------------------------------------------|
+    /// | 2    | invalid arguments                            |
+    /// | 10   | missing configuration                        |
+    /// | 11   | invalid configuration                        |
+    /// | 20   | cannot update the gitmojis                   |
+    /// | 21   | an update of the gitmojis is available       |
+    /// | 30   | invalid commit message                       |
+    /// | 40   | `git commit` failed, or cannot rewrite       |
+    /// | 41   | cannot run a git command                     |
+    /// | 50   | the hooks cannot be installed or removed     |
+    /// | 60   | cannot prompt                                |
+    /// | 70   | I/O error                                    |
+    /// | 80   | `gitmoji doctor` found a problem             |
+    #[must_use]
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::InvalidGitArguments(_)
+            | Self::UnknownCatalogSource(_)
+            | Self::UnknownConfigKey(_) => EXIT_INVALID_ARGUMENTS,
+            Self::MissingConfigFile | Self::CannotGetProjectConfigFile(_) => EXIT_NO_CONFIG,
+            Self::InvalidUrlError(_)
+            | Self::InvalidConfigFile { .. }
+            | Self::InvalidConfig(_)
+            | Self::InvalidConfigValue { .. }
+            | Self::UnsupportedConfigVersion(_)
+            | Self::TomlSerializeError(_)
+            | Self::TomlDeserializeError(_) => EXIT_INVALID_CONFIG,
+            Self::CannotFetchGitmojis(_)
+            | Self::CannotUpdateConfig { .. }
+            | Self::InvalidCatalog(_)
+            | Self::CatalogVerificationFailed { .. } => EXIT_CANNOT_UPDATE,
+            Self::InvalidCommitMessage(_) | Self::NoAllowedGitmoji => EXIT_INVALID_COMMIT_MESSAGE,
+            Self::CatalogUpdateAvailable(_) => EXIT_UPDATE_AVAILABLE,
+            Self::FailToCommit { .. } | Self::CannotRewriteCommit { .. } => EXIT_FAIL_TO_COMMIT,
+            Self::GitCommandError(_) => EXIT_GIT_COMMAND,
+            Self::HookAlreadyExists(_) => EXIT_HOOK,
+            Self::DialoguerError(_) | Self::PromptError(_) => EXIT_PROMPT,
+            Self::DoctorFailed(_) => EXIT_DOCTOR,
+            Self::IoError(_) => EXIT_IO,
+        }
+    }
+
+    /// A stable name for the error, used by the JSON output
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::CannotFetchGitmojis(_) => "cannot_fetch_gitmojis",
+            Self::CannotGetProjectConfigFile(_) => "cannot_get_project_config_file",
+            Self::CannotUpdateConfig { .. } => "cannot_update_config",
+            Self::InvalidCatalog(_) => "invalid_catalog",
+            Self::CatalogVerificationFailed { .. } => "catalog_verification_failed",
+            Self::CatalogUpdateAvailable(_) => "catalog_update_available",
+            Self::UnknownCatalogSource(_) => "unknown_catalog_source",
+            Self::CannotRewriteCommit { .. } => "cannot_rewrite_commit",
+            Self::FailToCommit { .. } => "fail_to_commit",
+            Self::InvalidGitArguments(_) => "invalid_git_arguments",
+            Self::InvalidCommitMessage(_) => "invalid_commit_message",
+            Self::NoAllowedGitmoji => "no_allowed_gitmoji",
+            Self::HookAlreadyExists(_) => "hook_already_exists",
+            Self::MissingConfigFile => "missing_config_file",
+            Self::InvalidConfigFile { .. } => "invalid_config_file",
+            Self::InvalidConfig(_) => "invalid_config",
+            Self::UnknownConfigKey(_) => "unknown_config_key",
+            Self::InvalidConfigValue { .. } => "invalid_config_value",
+            Self::UnsupportedConfigVersion(_) => "unsupported_config_version",
+            Self::DoctorFailed(_) => "doctor_failed",
+            Self::IoError(_) => "io_error",
+            Self::GitCommandError(_) => "git_command_error",
+            Self::InvalidUrlError(_) => "invalid_url",
+            Self::TomlSerializeError(_) => "toml_serialize_error",
+            Self::TomlDeserializeError(_) => "toml_deserialize_error",
+            Self::DialoguerError(_) => "dialoguer_error",
+            Self::PromptError(_) => "prompt_error",
+        }
+    }
+}
+
+/// Write the error report on stderr
+pub fn report_error(err: &Error, format: ErrorFormat) {
+    match format {
+        ErrorFormat::Human => eprintln!("⚠️  {err}"),
+        ErrorFormat::Json => {
+            let mut report = serde_json::json!({
+                "kind": err.kind(),
+                "code": err.exit_code(),
+                "message": err.to_string(),
+            });
+            if let Error::FailToCommit { status, stderr } = err {
+                report["status"] = serde_json::json!(status);
+                report["stderr"] = serde_json::json!(stderr);
+            }
+            if let Error::InvalidConfigFile {
+                file, line, column, ..
+            } = err
+            {
+                report["file"] = serde_json::json!(file);
+                report["line"] = serde_json::json!(line);
+                report["column"] = serde_json::json!(column);
+            }
+            eprintln!("{report}");
+        }
+    }
+}
+
+fn display_status(status: Option<i32>) -> String {
+    status.map_or_else(|| String::from("a signal"), |code| format!("status {code}"))
+}
+
+fn display_stderr(stderr: &str) -> String {
+    let stderr = stderr.trim();
+    if stderr.is_empty() {
+        String::new()
+    } else {
+        format!(": {stderr}")
+    }
+}
+
+fn display_position(line: Option<usize>, column: Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!(":{line}:{column}"),
+        _ => String::new(),
+    }
 }
 
 /// Gitmojis result
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::check;
+
+    use super::*;
+
+    #[test]
+    fn should_display_commit_failure() {
+        let err = Error::FailToCommit {
+            status: Some(1),
+            stderr: String::from("error: gpg failed to sign the data\n"),
+        };
+
+        check!(
+            err.to_string()
+                == "Fail to commit, git exited with status 1: error: gpg failed to sign the data"
+        );
+        check!(err.exit_code() == EXIT_FAIL_TO_COMMIT);
+    }
+
+    #[test]
+    fn should_map_update_failure() {
+        let err = Error::CannotUpdateConfig {
+            url: String::from("https://example.com"),
+            source: Box::new(Error::MissingConfigFile),
+        };
+
+        check!(err.exit_code() == EXIT_CANNOT_UPDATE);
+        check!(err.kind() == "cannot_update_config");
+    }
+}
@@ -1,5 +1,7 @@
-use std::process::ExitStatus;
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
 
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
 
 #[derive(Debug, thiserror::Error)]
@@ -13,43 +15,93 @@ pub struct GitCommandError {
 
 type Result<T> = std::result::Result<T, GitCommandError>;
 
-pub(crate) async fn commit(
+/// The `git commit` arguments
+pub(crate) fn commit_args(
     all: bool,
     amend: bool,
     signed: bool,
     commit_title: &str,
     description: Option<&str>,
-) -> Result<ExitStatus> {
-    let mut args = vec!["commit"];
+    extra_args: &[String],
+) -> Vec<String> {
+    let mut args = vec![String::from("commit")];
     if all {
-        args.push("--all");
+        args.push(String::from("--all"));
     }
     if amend {
-        args.push("--amend");
+        args.push(String::from("--amend"));
     }
     if signed {
-        args.push("-S");
+        args.push(String::from("-S"));
     }
-    args.push("-m");
-    args.push(commit_title);
+    args.push(String::from("-m"));
+    args.push(commit_title.to_string());
     if let Some(description) = description {
-        args.push("-m");
-        args.push(description);
+        args.push(String::from("-m"));
+        args.push(description.to_string());
     }
-    let status = Command::new("git")
-        .args(&args)
-        .status()
+    args.extend_from_slice(extra_args);
+    args
+}
+
+/// Run `git commit` with the arguments, returns the exit status and the error output
+///
+/// Without `capture_stderr`, git keeps writing on the terminal and the error output is empty
+pub(crate) async fn commit(args: &[String], capture_stderr: bool) -> Result<(ExitStatus, String)> {
+    let command_error = |source| GitCommandError {
+        source,
+        command: format!("git {}", args.join(" ")),
+    };
+    if !capture_stderr {
+        let status = Command::new("git")
+            .args(args)
+            .status()
+            .await
+            .map_err(command_error)?;
+        return Ok((status, String::new()));
+    }
+    let mut child = Command::new("git")
+        .args(args)
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(command_error)?;
+
+    // Forward the error output while keeping it for the error report
+    let mut stderr = vec![];
+    if let Some(mut pipe) = child.stderr.take() {
+        let mut buffer = [0; 1024];
+        loop {
+            let len = pipe.read(&mut buffer).await.map_err(command_error)?;
+            if len == 0 {
+                break;
+            }
+            let _ = tokio::io::stderr().write_all(&buffer[..len]).await;
+            stderr.extend_from_slice(&buffer[..len]);
+        }
+    }
+    let status = child.wait().await.map_err(command_error)?;
+
+    Ok((status, String::from_utf8_lossy(&stderr).to_string()))
+}
+
+pub(crate) async fn get_config_value(config_key: &str) -> Result<String> {
+    let args = ["config", "--get", config_key];
+    let output = Command::new("git")
+        .args(args)
+        .output()
         .await
         .map_err(|source| GitCommandError {
             source,
             command: format!("git {}", args.join(" ")),
         })?;
 
-    Ok(status)
+    let result = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(result)
 }
 
-pub(crate) async fn get_config_value(config_key: &str) -> Result<String> {
-    let args = ["config", "--get", config_key];
+/// The editor used by git, from `GIT_EDITOR`, `core.editor`, `VISUAL`, or `EDITOR`
+async fn get_editor() -> Result<String> {
+    let args = ["var", "GIT_EDITOR"];
     let output = Command::new("git")
         .args(args)
         .output()
@@ -63,6 +115,29 @@ pub(crate) async fn get_config_value(config_key: &str) -> Result<String> {
     Ok(result)
 }
 
+/// Open the file in the git editor, and wait for it
+pub(crate) async fn edit_file(file: &Path) -> Result<()> {
+    let editor = get_editor().await?;
+    let command = format!("{editor} {}", file.display());
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$@\""))
+        .arg(&editor)
+        .arg(file)
+        .status()
+        .await
+        .map_err(|source| GitCommandError {
+            source,
+            command: command.clone(),
+        })?;
+    if !status.success() {
+        let source =
+            std::io::Error::new(std::io::ErrorKind::Other, format!("exited with {status}"));
+        return Err(GitCommandError { source, command });
+    }
+    Ok(())
+}
+
 pub(crate) async fn has_staged_changes() -> Result<bool> {
     let args = ["status", "--porcelain"];
     let output = Command::new("git")
@@ -83,7 +158,6 @@ pub(crate) async fn has_staged_changes() -> Result<bool> {
     Ok(false)
 }
 
-#[cfg(feature = "hook")]
 pub(crate) async fn get_git_dir() -> Result<std::path::PathBuf> {
     let args = ["rev-parse", "--absolute-git-dir"];
     let output = Command::new("git")
@@ -99,3 +173,217 @@ pub(crate) async fn get_git_dir() -> Result<std::path::PathBuf> {
     let result = std::path::PathBuf::from(result);
     Ok(result)
 }
+
+/// The path of a file in the git directory, relative to the current directory
+#[cfg(feature = "hook")]
+pub(crate) async fn get_git_path(path: &str) -> Result<std::path::PathBuf> {
+    let result = output(&["rev-parse", "--git-path", path]).await?;
+    Ok(std::path::PathBuf::from(result.trim()))
+}
+
+async fn output(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .await
+        .map_err(|source| GitCommandError {
+            source,
+            command: format!("git {}", args.join(" ")),
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(GitCommandError {
+            source: std::io::Error::new(std::io::ErrorKind::Other, stderr),
+            command: format!("git {}", args.join(" ")),
+        });
+    }
+
+    let result = String::from_utf8_lossy(&output.stdout).to_string();
+    Ok(result)
+}
+
+/// The git version, like `git version 2.43.0`
+pub(crate) async fn get_version() -> Result<String> {
+    let result = output(&["--version"]).await?;
+    Ok(result.trim().to_string())
+}
+
+pub(crate) async fn is_inside_work_tree() -> bool {
+    let result = output(&["rev-parse", "--is-inside-work-tree"]).await;
+    result.is_ok_and(|it| it.trim() == "true")
+}
+
+pub(crate) async fn get_current_branch() -> Result<Option<String>> {
+    let result = output(&["branch", "--show-current"]).await?;
+    let result = result.trim();
+    Ok((!result.is_empty()).then(|| result.to_string()))
+}
+
+pub(crate) async fn get_changed_files(all: bool) -> Result<Vec<String>> {
+    let mut result = output(&["diff", "--cached", "--name-only"])
+        .await?
+        .lines()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if all {
+        for file in output(&["diff", "--name-only"]).await?.lines() {
+            if !result.iter().any(|it| it == file) {
+                result.push(file.to_string());
+            }
+        }
+    }
+    Ok(result)
+}
+
+pub(crate) async fn get_commit_files(commit: &str) -> Result<Vec<String>> {
+    let args = [
+        "diff-tree",
+        "--no-commit-id",
+        "--name-only",
+        "-r",
+        "--root",
+        commit,
+    ];
+    let result = output(&args).await?.lines().map(str::to_string).collect();
+    Ok(result)
+}
+
+/// List the commits (id and subject) of the range, without the merge commits
+pub(crate) async fn list_commits(range: &str) -> Result<Vec<(String, String)>> {
+    let args = ["log", "--no-merges", "--format=%H%x09%s", range];
+    let result = output(&args)
+        .await?
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(id, subject)| (id.to_string(), subject.to_string()))
+        .collect();
+    Ok(result)
+}
+
+/// List the recent commits (id and subject) of the current branch, without the merge commits
+pub(crate) async fn list_recent_commits(count: usize) -> Result<Vec<(String, String)>> {
+    let count = count.to_string();
+    let args = [
+        "log",
+        "--no-merges",
+        "--format=%H%x09%s",
+        "-n",
+        &count,
+        "HEAD",
+    ];
+    let result = output(&args)
+        .await?
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(id, subject)| (id.to_string(), subject.to_string()))
+        .collect();
+    Ok(result)
+}
+
+// The parent of the target, or `--root` for the first commit
+async fn rebase_upstream(target: &str) -> String {
+    let parent = format!("{target}~1");
+    let has_parent = output(&["rev-parse", "--verify", "--quiet", &parent])
+        .await
+        .is_ok();
+    if has_parent {
+        parent
+    } else {
+        String::from("--root")
+    }
+}
+
+/// If a merge commit would be rewritten by an autosquash into the target
+pub(crate) async fn has_merges_since(target: &str) -> Result<bool> {
+    let upstream = rebase_upstream(target).await;
+    let range = if upstream == "--root" {
+        String::from("HEAD")
+    } else {
+        format!("{upstream}..HEAD")
+    };
+    let result = output(&["rev-list", "--merges", &range]).await?;
+    Ok(!result.trim().is_empty())
+}
+
+/// Apply the fixup commits with a non-interactive `git rebase --autosquash`
+pub(crate) async fn autosquash(target: &str) -> Result<()> {
+    let upstream = rebase_upstream(target).await;
+    output(&[
+        "-c",
+        "sequence.editor=:",
+        "rebase",
+        "--interactive",
+        "--autosquash",
+        "--autostash",
+        &upstream,
+    ])
+    .await?;
+    Ok(())
+}
+
+/// Resolve the commit id of the revision, and read its message
+pub(crate) async fn get_commit_message(rev: &str) -> Result<(String, String)> {
+    let commit = format!("{rev}^{{commit}}");
+    let id = output(&["rev-parse", "--verify", "--quiet", &commit])
+        .await?
+        .trim()
+        .to_string();
+    let message = output(&["log", "-1", "--format=%B", &id]).await?;
+    Ok((id, message.trim().to_string()))
+}
+
+/// If the commit is the `HEAD` commit
+pub(crate) async fn is_head(commit: &str) -> Result<bool> {
+    let head = output(&["rev-parse", "HEAD"]).await?;
+    Ok(head.trim() == commit)
+}
+
+/// If the commit is in the current branch
+pub(crate) async fn is_ancestor_of_head(commit: &str) -> Result<bool> {
+    let result = output(&["merge-base", "--is-ancestor", commit, "HEAD"])
+        .await
+        .is_ok();
+    Ok(result)
+}
+
+/// If the commit is in a remote branch
+pub(crate) async fn is_published(commit: &str) -> Result<bool> {
+    let result = output(&["branch", "--remotes", "--contains", commit]).await?;
+    Ok(!result.trim().is_empty())
+}
+
+/// Read a file of a remote repository, with a shallow clone in a temporary directory
+pub(crate) async fn read_remote_file(
+    repository: &str,
+    rev: Option<&str>,
+    file: &str,
+) -> Result<String> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|it| it.subsec_nanos())
+        .unwrap_or_default();
+    let dir = std::env::temp_dir().join(format!("gitmoji-{}-{nanos}", std::process::id()));
+    let dir_arg = dir.to_string_lossy().to_string();
+
+    let mut args = vec!["clone", "--quiet", "--depth", "1", "--no-checkout"];
+    if let Some(rev) = rev {
+        args.extend(["--branch", rev]);
+    }
+    args.extend([repository, &dir_arg]);
+    let result = match output(&args).await {
+        Ok(_) => {
+            let object = format!("HEAD:{file}");
+            output(&["-C", &dir_arg, "show", &object]).await
+        }
+        Err(err) => Err(err),
+    };
+    let _ = tokio::fs::remove_dir_all(&dir).await;
+
+    result
+}
+
+pub(crate) async fn get_top_level() -> Result<std::path::PathBuf> {
+    let result = output(&["rev-parse", "--show-toplevel"]).await?;
+    Ok(std::path::PathBuf::from(result.trim()))
+}
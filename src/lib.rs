@@ -13,43 +13,131 @@ mod cli;
 mod cmd;
 mod error;
 mod git;
+mod message;
 mod model;
+mod prompt;
 
 use std::io::stdout;
 
 use clap::CommandFactory;
 use clap_complete::generate;
-use console::Term;
 
 pub use self::cli::*;
 pub use self::cmd::{
-    config as gitmoji_config, read_config_or_default, read_config_or_fail, write_config,
+    config as gitmoji_config, create_config, get_catalog_file, get_config_file,
+    read_config_or_default, read_config_or_fail, write_config, CONFIG_VERSION,
 };
 pub use self::error::*;
+pub use self::message::*;
 pub use self::model::*;
+pub use self::prompt::*;
+
+/// Exit code when the arguments are invalid, like clap usage errors
+pub const EXIT_INVALID_ARGUMENTS: i32 = 2;
 
 /// Exit code when a configuration is require but not found
 pub const EXIT_NO_CONFIG: i32 = 10;
 
+/// Exit code when the configuration is invalid
+pub const EXIT_INVALID_CONFIG: i32 = 11;
+
 /// Exit code when a configuration cannot been updated
 pub const EXIT_CANNOT_UPDATE: i32 = 20;
 
+/// Exit code when an update of the catalog is available, with `gitmoji update --check`
+pub const EXIT_UPDATE_AVAILABLE: i32 = 21;
+
+/// Exit code when the commit message is rejected
+pub const EXIT_INVALID_COMMIT_MESSAGE: i32 = 30;
+
+/// Exit code when the `git commit` command fails
+pub const EXIT_FAIL_TO_COMMIT: i32 = 40;
+
+/// Exit code when a git command cannot be run
+pub const EXIT_GIT_COMMAND: i32 = 41;
+
+/// Exit code when the hooks cannot be installed or removed
+pub const EXIT_HOOK: i32 = 50;
+
+/// Exit code when a prompt cannot be answered
+pub const EXIT_PROMPT: i32 = 60;
+
+/// Exit code on I/O errors
+pub const EXIT_IO: i32 = 70;
+
+/// Exit code when `gitmoji doctor` finds a problem
+pub const EXIT_DOCTOR: i32 = 80;
+
 /// Running the gitmoji code
 ///
 /// # Errors
 /// If the command fail
-pub async fn run(settings: Settings, term: &Term) -> Result<()> {
+pub async fn run(settings: Settings, prompter: &dyn Prompter) -> Result<()> {
+    let error_format = settings.error_format();
     match settings.command {
-        Command::Init { default } => gitmoji_config(default, term).await,
-        Command::Commit { all, amend } => cmd::commit(all, amend, term).await,
-        Command::Update { url } => cmd::update_config(url).await,
+        Command::Init { default } => gitmoji_config(default, prompter).await,
+        Command::Config(op) => match op {
+            ConfigOperation::Get { key, target } => cmd::config_get(&key, target).await,
+            ConfigOperation::Set { key, value, target } => {
+                cmd::config_set(&key, &value, target).await
+            }
+            ConfigOperation::Unset { key, target } => cmd::config_unset(&key, target).await,
+            ConfigOperation::List { target } => cmd::config_list(target).await,
+            ConfigOperation::Edit { target } => cmd::config_edit(target).await,
+            ConfigOperation::Validate => cmd::config_validate().await,
+            ConfigOperation::Schema { target } => cmd::config_print_schema(target),
+            ConfigOperation::Migrate { dry_run } => cmd::migrate_config(dry_run).await,
+        },
+        Command::Commit {
+            all,
+            amend,
+            dry_run,
+            print,
+            resume,
+            git_args,
+        } => {
+            let mode = match (dry_run, print) {
+                (true, _) => cmd::CommitMode::DryRun,
+                (false, true) => cmd::CommitMode::Print,
+                (false, false) => cmd::CommitMode::Commit,
+            };
+            cmd::commit(all, amend, mode, resume, git_args, error_format, prompter).await
+        }
+        Command::Update { url, source, check } => cmd::update_config(url, source, check).await,
         Command::List => cmd::list().await,
+        Command::Doctor { offline } => cmd::doctor(offline, prompter).await,
         Command::Search { text } => cmd::search(&text).await,
+        Command::Validate { range, branch } => cmd::validate(&range, branch).await,
+        Command::Fixup {
+            kind,
+            count,
+            all,
+            autosquash,
+            git_args,
+        } => {
+            cmd::fixup(
+                kind,
+                count,
+                all,
+                autosquash,
+                git_args,
+                error_format,
+                prompter,
+            )
+            .await
+        }
+        Command::Reword { rev } => cmd::reword(rev, error_format, prompter).await,
         #[cfg(feature = "hook")]
         Command::Hook(op) => match op {
-            HookOperation::Add => cmd::create_hook().await,
+            HookOperation::Add { chain } => cmd::create_hook(chain).await,
             HookOperation::Remove => cmd::remove_hook().await,
-            HookOperation::Apply { dest, source } => cmd::apply_hook(dest, source, term).await,
+            HookOperation::Status => cmd::hook_status().await,
+            HookOperation::Snippet { manager } => {
+                cmd::hook_snippet(manager);
+                Ok(())
+            }
+            HookOperation::Apply { dest, source } => cmd::apply_hook(dest, source, prompter).await,
+            HookOperation::Validate { dest } => cmd::validate_hook(dest).await,
         },
         Command::Completion { shell } => {
             let mut cmd = <Settings as CommandFactory>::command();
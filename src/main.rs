@@ -1,18 +1,22 @@
 use clap::Parser;
 use console::Term;
-use gitmoji_rs::{run, Settings};
+use gitmoji_rs::{report_error, run, DialoguerPrompter, Settings};
 use tracing::error;
 
 #[tokio::main]
 async fn main() {
     let settings = Settings::parse();
+    let error_format = settings.error_format();
 
     if settings.verbose() {
         tracing_subscriber::fmt::init();
     }
 
     let term = Term::stderr();
-    if let Err(err) = run(settings, &term).await {
+    let prompter = DialoguerPrompter::new(&term);
+    if let Err(err) = run(settings, &prompter).await {
         error!("Oops, {err}");
+        report_error(&err, error_format);
+        std::process::exit(err.exit_code());
     }
 }
@@ -0,0 +1,626 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::{ConventionalEmojiCommit, EmojiFormat, Error, Gitmoji};
+
+const BREAKING_CHANGE: &str = "BREAKING CHANGE";
+
+/// A gitmoji commit message
+///
+/// The message follows the conventional emoji commits specification when a type is set,
+/// like `🐛fix(api)!: Fix the title`, otherwise the default gitmoji specification,
+/// like `🐛 Fix the title`, with the scope written as is before the title.
+///
+/// Without a type, the message does not round-trip exactly:
+/// - the scope has no delimiter, so the parsed message has no scope, and the scope stays in the title,
+/// - a breaking change has no `!` marker, so [`CommitMessageBuilder::build`] adds
+///   a `BREAKING CHANGE: <title>` trailer, unless the trailers already have one.
+///
+/// ```
+/// # use gitmoji_rs::CommitMessage;
+/// let message = CommitMessage::builder(":bug:", "Fix the title")
+///     .body("The title was truncated")
+///     .trailer("Refs", "#42")
+///     .build();
+/// let text = message.to_string();
+/// assert_eq!(text, ":bug: Fix the title\n\nThe title was truncated\n\nRefs: #42");
+/// assert_eq!(text.parse::<CommitMessage>().unwrap(), message);
+///
+/// let message = CommitMessage::builder(":boom:", "Drop the v1 API")
+///     .scope("api: ")
+///     .breaking(true)
+///     .build();
+/// let text = message.to_string();
+/// assert_eq!(text, ":boom: api: Drop the v1 API\n\nBREAKING CHANGE: Drop the v1 API");
+/// let parsed = text.parse::<CommitMessage>().unwrap();
+/// assert_eq!(parsed.scope(), None);
+/// assert_eq!(parsed.title(), "api: Drop the v1 API");
+/// assert!(parsed.breaking());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitMessage {
+    gitmoji: String,
+    r#type: Option<String>,
+    scope: Option<String>,
+    breaking: bool,
+    title: String,
+    body: Option<String>,
+    trailers: Vec<(String, String)>,
+}
+
+impl CommitMessage {
+    /// Create a builder with the gitmoji (emoji or code) and the title
+    #[must_use]
+    pub fn builder(gitmoji: impl Into<String>, title: impl Into<String>) -> CommitMessageBuilder {
+        CommitMessageBuilder {
+            message: Self {
+                gitmoji: gitmoji.into(),
+                r#type: None,
+                scope: None,
+                breaking: false,
+                title: title.into(),
+                body: None,
+                trailers: vec![],
+            },
+        }
+    }
+
+    /// Create a builder for the default specification
+    #[must_use]
+    pub fn builder_with_gitmoji(
+        gitmoji: &Gitmoji,
+        format: &EmojiFormat,
+        title: impl Into<String>,
+    ) -> CommitMessageBuilder {
+        let gitmoji = match format {
+            EmojiFormat::UseCode => gitmoji.code(),
+            EmojiFormat::UseEmoji => gitmoji.emoji(),
+        };
+        Self::builder(gitmoji, title)
+    }
+
+    /// Create a builder for the conventional emoji commits specification
+    #[must_use]
+    pub fn builder_with_conventional_emoji(
+        emoji: &ConventionalEmojiCommit,
+        format: &EmojiFormat,
+        title: impl Into<String>,
+    ) -> CommitMessageBuilder {
+        let gitmoji = match format {
+            EmojiFormat::UseCode => emoji.code(),
+            EmojiFormat::UseEmoji => emoji.emoji(),
+        };
+        Self::builder(gitmoji, title).r#type(emoji.r#type())
+    }
+
+    /// The gitmoji, as an emoji or a code
+    #[must_use]
+    pub fn gitmoji(&self) -> &str {
+        self.gitmoji.as_ref()
+    }
+
+    /// The conventional commit type
+    #[must_use]
+    pub fn r#type(&self) -> Option<&str> {
+        self.r#type.as_deref()
+    }
+
+    /// The scope
+    #[must_use]
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    /// If the commit contains a breaking change
+    #[must_use]
+    pub const fn breaking(&self) -> bool {
+        self.breaking
+    }
+
+    /// The title
+    #[must_use]
+    pub fn title(&self) -> &str {
+        self.title.as_ref()
+    }
+
+    /// The body, without the trailers
+    #[must_use]
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    /// The trailers, like `Refs: #42`
+    #[must_use]
+    pub fn trailers(&self) -> &[(String, String)] {
+        self.trailers.as_ref()
+    }
+
+    /// The first line of the message
+    #[must_use]
+    pub fn header(&self) -> String {
+        let Self {
+            gitmoji,
+            r#type,
+            scope,
+            breaking,
+            title,
+            ..
+        } = self;
+        let scope = scope.as_deref().unwrap_or_default();
+        match r#type {
+            Some(r#type) => {
+                let scope = if scope.is_empty() {
+                    String::new()
+                } else {
+                    format!("({scope})")
+                };
+                let breaking = if *breaking { "!" } else { "" };
+                format!("{gitmoji}{type}{scope}{breaking}: {title}")
+            }
+            None => format!("{gitmoji} {scope}{title}"),
+        }
+    }
+
+    /// The message after the header, with the body and the trailers
+    #[must_use]
+    pub fn description(&self) -> Option<String> {
+        let trailers = self
+            .trailers
+            .iter()
+            .map(|(key, value)| format!("{key}: {value}"))
+            .collect::<Vec<_>>();
+
+        let paragraphs = self
+            .body
+            .iter()
+            .cloned()
+            .chain((!trailers.is_empty()).then(|| trailers.join("\n")))
+            .collect::<Vec<_>>();
+        (!paragraphs.is_empty()).then(|| paragraphs.join("\n\n"))
+    }
+}
+
+impl Display for CommitMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.header())?;
+        if let Some(description) = self.description() {
+            write!(f, "\n\n{description}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CommitMessage {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, rest) = s.trim().split_once('\n').unwrap_or((s.trim(), ""));
+        let Some((gitmoji, header_rest)) = split_gitmoji(header) else {
+            return Err(Error::InvalidCommitMessage(format!(
+                "'{header}' does not start with a gitmoji"
+            )));
+        };
+        let header_fields = parse_header(header_rest);
+        // Without a type, the scope is written as is, so it is part of the title
+        let Header {
+            r#type,
+            scope,
+            breaking,
+            title,
+        } = if header_fields.r#type.is_some() {
+            header_fields
+        } else {
+            Header {
+                r#type: None,
+                scope: None,
+                breaking: false,
+                title: header_rest.trim(),
+            }
+        };
+        if title.is_empty() {
+            return Err(Error::InvalidCommitMessage(format!(
+                "'{header}' has an empty title"
+            )));
+        }
+
+        let mut paragraphs = rest
+            .trim()
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect::<Vec<_>>();
+        let trailers = paragraphs
+            .last()
+            .and_then(|paragraph| {
+                paragraph
+                    .lines()
+                    .map(parse_trailer)
+                    .collect::<Option<Vec<_>>>()
+            })
+            .unwrap_or_default();
+        if !trailers.is_empty() {
+            paragraphs.pop();
+        }
+        let body = (!paragraphs.is_empty()).then(|| paragraphs.join("\n\n"));
+        let breaking = breaking || trailers.iter().any(|(key, _)| is_breaking_key(key));
+
+        Ok(Self {
+            gitmoji: gitmoji.to_string(),
+            r#type: r#type.map(str::to_string),
+            scope: scope.map(str::to_string),
+            breaking,
+            title: title.to_string(),
+            body,
+            trailers,
+        })
+    }
+}
+
+/// A builder for [`CommitMessage`]
+#[derive(Debug, Clone)]
+pub struct CommitMessageBuilder {
+    message: CommitMessage,
+}
+
+impl CommitMessageBuilder {
+    /// Set the conventional commit type
+    #[must_use]
+    pub fn r#type(mut self, r#type: impl Into<String>) -> Self {
+        self.message.r#type = Some(r#type.into()).filter(|it| !it.is_empty());
+        self
+    }
+
+    /// Set the scope, an empty scope is ignored
+    #[must_use]
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.message.scope = Some(scope.into()).filter(|it| !it.is_empty());
+        self
+    }
+
+    /// Mark the commit as a breaking change
+    ///
+    /// Without a type, a breaking change is written as a `BREAKING CHANGE` trailer.
+    #[must_use]
+    pub fn breaking(mut self, breaking: bool) -> Self {
+        self.message.breaking = breaking;
+        self
+    }
+
+    /// Set the body, an empty body is ignored
+    #[must_use]
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.message.body = Some(body.into()).filter(|it| !it.is_empty());
+        self
+    }
+
+    /// Add a trailer, like `Refs: #42`
+    #[must_use]
+    pub fn trailer(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.message.trailers.push((key.into(), value.into()));
+        self
+    }
+
+    /// Build the message
+    ///
+    /// A `BREAKING CHANGE` trailer marks the commit as a breaking change.
+    #[must_use]
+    pub fn build(self) -> CommitMessage {
+        let mut message = self.message;
+        let has_breaking_trailer = message.trailers.iter().any(|(key, _)| is_breaking_key(key));
+        message.breaking |= has_breaking_trailer;
+        if message.breaking && message.r#type.is_none() && !has_breaking_trailer {
+            let title = message.title.clone();
+            message
+                .trailers
+                .push((String::from(BREAKING_CHANGE), title));
+        }
+        message
+    }
+}
+
+/// The header after the gitmoji
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Header<'s> {
+    pub(crate) r#type: Option<&'s str>,
+    pub(crate) scope: Option<&'s str>,
+    pub(crate) breaking: bool,
+    pub(crate) title: &'s str,
+}
+
+/// Parse the header following the gitmoji
+///
+/// The conventional `type(scope)!: title` is attached to the gitmoji,
+/// while the default `(scope): title` is separated by a space.
+pub(crate) fn parse_header(header: &str) -> Header<'_> {
+    let attached = !header.starts_with(char::is_whitespace);
+    let header = header.trim();
+    let plain = Header {
+        r#type: None,
+        scope: None,
+        breaking: false,
+        title: header,
+    };
+
+    let type_len = header
+        .find(|ch: char| !(ch.is_alphanumeric() || ch == '-' || ch == '_'))
+        .unwrap_or(header.len());
+    let (r#type, mut rest) = header.split_at(type_len);
+    let mut scope = None;
+    if let Some(inner) = rest.strip_prefix('(') {
+        let Some(end) = inner.find(')') else {
+            return plain;
+        };
+        scope = Some(&inner[..end]);
+        rest = &inner[end + 1..];
+    }
+    let breaking = rest.starts_with('!');
+    let rest = rest.strip_prefix('!').unwrap_or(rest);
+    let Some(title) = rest.strip_prefix(':') else {
+        return plain;
+    };
+    if (r#type.is_empty() && scope.is_none()) || (!r#type.is_empty() && !attached) {
+        return plain;
+    }
+
+    Header {
+        r#type: (!r#type.is_empty()).then_some(r#type),
+        scope: scope.filter(|scope| !scope.is_empty()),
+        breaking,
+        title: title.trim_start(),
+    }
+}
+
+// A gitmoji code like `:bug:`, or the leading emoji characters
+fn split_gitmoji(header: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = header.strip_prefix(':') {
+        let end = rest.find(':')?;
+        let code = &rest[..end];
+        let valid = !code.is_empty()
+            && code
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '+'));
+        return valid.then(|| header.split_at(end + 2));
+    }
+
+    if !header.chars().next().is_some_and(is_emoji) {
+        return None;
+    }
+    let len = header
+        .find(|ch| !(is_emoji(ch) || is_emoji_component(ch)))
+        .unwrap_or(header.len());
+    Some(header.split_at(len))
+}
+
+// The pictographic blocks of the emojis, the gitmojis are all in these blocks
+const fn is_emoji(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x00A9
+            | 0x00AE
+            | 0x203C
+            | 0x2049
+            | 0x2122
+            | 0x2139
+            | 0x2194..=0x21AA
+            | 0x231A..=0x23FF
+            | 0x24C2
+            | 0x25AA..=0x25FE
+            | 0x2600..=0x27BF
+            | 0x2934..=0x2935
+            | 0x2B05..=0x2B55
+            | 0x3030
+            | 0x303D
+            | 0x3297
+            | 0x3299
+            | 0x1F000..=0x1FAFF
+    )
+}
+
+// The variation selector, the zero width joiner, the keycap, and the tags
+const fn is_emoji_component(ch: char) -> bool {
+    matches!(ch as u32, 0xFE0F | 0x200D | 0x20E3 | 0xE0020..=0xE007F)
+}
+
+fn parse_trailer(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(": ")?;
+    let valid = is_breaking_key(key)
+        || (!key.is_empty() && key.chars().all(|ch| ch.is_alphanumeric() || ch == '-'));
+    valid.then(|| (key.to_string(), value.trim().to_string()))
+}
+
+fn is_breaking_key(key: &str) -> bool {
+    key == BREAKING_CHANGE || key == "BREAKING-CHANGE"
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+
+    #[test]
+    fn should_render_default_message() {
+        let message = CommitMessage::builder("🐛", "Fix the title")
+            .scope("api: ")
+            .build();
+
+        check!(message.to_string() == "🐛 api: Fix the title");
+    }
+
+    #[test]
+    fn should_render_conventional_message() {
+        let emoji = ConventionalEmojiCommit::new(
+            String::from("✨"),
+            String::from(":sparkles:"),
+            String::from("feat"),
+            None,
+        );
+        let message =
+            CommitMessage::builder_with_conventional_emoji(&emoji, &EmojiFormat::UseCode, "Add")
+                .scope("cli")
+                .breaking(true)
+                .body("Some details")
+                .build();
+
+        check!(message.to_string() == ":sparkles:feat(cli)!: Add\n\nSome details");
+    }
+
+    #[test]
+    fn should_render_breaking_trailer_without_type() {
+        let message = CommitMessage::builder(":boom:", "Drop the v1 API")
+            .breaking(true)
+            .build();
+
+        check!(message.to_string() == ":boom: Drop the v1 API\n\nBREAKING CHANGE: Drop the v1 API");
+        check!(
+            message.trailers()
+                == [(
+                    String::from("BREAKING CHANGE"),
+                    String::from("Drop the v1 API")
+                )]
+        );
+        let_assert!(Ok(parsed) = message.to_string().parse::<CommitMessage>());
+        check!(parsed == message);
+    }
+
+    #[test]
+    fn should_keep_default_scope_in_title() {
+        let message = CommitMessage::builder("🐛", "Fix the title")
+            .scope("api: ")
+            .build();
+
+        let result = message.to_string().parse::<CommitMessage>();
+
+        let_assert!(Ok(parsed) = result);
+        check!(parsed.scope() == None);
+        check!(parsed.title() == "api: Fix the title");
+        check!(parsed.to_string() == message.to_string());
+    }
+
+    #[test]
+    fn should_parse_conventional_message() {
+        let text = "✨feat(api,web)!: Add the search\n\nA long\nbody\n\nSecond paragraph\n\nRefs: #42\nCo-authored-by: Jane <jane@example.com>\n";
+
+        let result = text.parse::<CommitMessage>();
+
+        let_assert!(Ok(message) = result);
+        check!(message.gitmoji() == "✨");
+        check!(message.r#type() == Some("feat"));
+        check!(message.scope() == Some("api,web"));
+        check!(message.breaking());
+        check!(message.title() == "Add the search");
+        check!(message.body() == Some("A long\nbody\n\nSecond paragraph"));
+        check!(message.trailers().len() == 2);
+    }
+
+    #[test]
+    fn should_parse_default_message() {
+        let result = ":zap: Faster: really\n\nBREAKING CHANGE: the API".parse::<CommitMessage>();
+
+        let_assert!(Ok(message) = result);
+        check!(message.gitmoji() == ":zap:");
+        check!(message.r#type() == None);
+        check!(message.scope() == None);
+        check!(message.title() == "Faster: really");
+        check!(message.body() == None);
+        check!(message.breaking());
+    }
+
+    #[test]
+    fn should_roundtrip_message() {
+        let message = CommitMessage::builder("⚡️", "Faster")
+            .r#type("perf")
+            .scope("db")
+            .trailer("Refs", "#1")
+            .build();
+
+        let result = message.to_string().parse::<CommitMessage>();
+
+        let_assert!(Ok(parsed) = result);
+        check!(parsed == message);
+    }
+
+    // Every combination of the parts, the default specification writes the scope
+    // as is before the title, so a scope without a type is parsed back in the title
+    #[test]
+    fn should_roundtrip_all_messages() {
+        for gitmoji in ["⚡️", ":zap:", "👨‍💻", "♻️"] {
+            for r#type in [None, Some("perf")] {
+                for scope in [None, Some("db"), Some("api,web")] {
+                    for breaking in [false, true] {
+                        for body in [None, Some("Some details"), Some("A long\nbody\n\nSecond")] {
+                            for trailers in
+                                [&[][..], &[("Refs", "#1")], &[("BREAKING CHANGE", "v2")]]
+                            {
+                                let mut builder = CommitMessage::builder(gitmoji, "Faster")
+                                    .r#type(r#type.unwrap_or_default())
+                                    .scope(scope.unwrap_or_default())
+                                    .breaking(breaking)
+                                    .body(body.unwrap_or_default());
+                                for (key, value) in trailers {
+                                    builder = builder.trailer(*key, *value);
+                                }
+                                let message = builder.build();
+                                let text = message.to_string();
+
+                                let result = text.parse::<CommitMessage>();
+
+                                let_assert!(Ok(parsed) = result, "{text:?}");
+                                check!(parsed.to_string() == text);
+                                match (r#type, scope) {
+                                    (None, Some(scope)) => {
+                                        check!(parsed.scope() == None, "{text:?}");
+                                        check!(parsed.title() == format!("{scope}Faster"));
+                                        check!(parsed.breaking() == message.breaking());
+                                        check!(parsed.trailers() == message.trailers());
+                                    }
+                                    _ => {
+                                        check!(parsed == message, "{text:?}");
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn should_reject_message_without_gitmoji() {
+        let result = "Add the search".parse::<CommitMessage>();
+
+        let_assert!(Err(Error::InvalidCommitMessage(_)) = result);
+    }
+
+    #[test]
+    fn should_reject_symbol_as_gitmoji() {
+        for text in [
+            "« Quoted » title",
+            "— Dash title",
+            "→ Arrow title",
+            ":not a code: title",
+        ] {
+            let result = text.parse::<CommitMessage>();
+
+            let_assert!(Err(Error::InvalidCommitMessage(_)) = result, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn should_split_emoji_sequences() {
+        check!(split_gitmoji("👨‍💻 Dev") == Some(("👨‍💻", " Dev")));
+        check!(split_gitmoji("⬆️ Bump") == Some(("⬆️", " Bump")));
+        check!(split_gitmoji("✨feat: Add") == Some(("✨", "feat: Add")));
+    }
+
+    #[test]
+    fn should_parse_header() {
+        check!(parse_header("feat(api)!: Breaking").scope == Some("api"));
+        check!(parse_header(" (api): Faster").scope == Some("api"));
+        check!(parse_header("feat: Add (api)").scope == None);
+        check!(parse_header(" Fix: the (api)").title == "Fix: the (api)");
+        check!(parse_header(" Fix (api) bug").title == "Fix (api) bug");
+    }
+}
@@ -1,18 +1,22 @@
-use std::{
-    collections::HashMap,
-    fmt::{self, Display},
-};
+use std::fmt::{self, Display};
+use std::path::PathBuf;
 
+use globset::GlobBuilder;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
+use tracing::warn;
 use url::Url;
 
 /// The default URL used for update
 pub const DEFAULT_URL: &str = "https://gitmoji.dev/api/gitmojis";
 /// The default url for conventional commits data
 pub const CONVENTIONAL_EMOJI_COMMITS_DEFAULT_URL: &str = "https://gist.githubusercontent.com/mluettecke/3f84a6a5c1c53ff6412828e601cd60ca/raw/9390c31c733ded384f65d37e063e041075016cdc/conventional-emoji-commits-types.json";
+/// The separator used to join several scopes
+pub const SCOPE_SEPARATOR: &str = ",";
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 /// The commit specification
 pub enum CommitSpecification {
     /// The default gitmoji format
@@ -21,7 +25,7 @@ pub enum CommitSpecification {
     ConventionalEmojiCommits,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 /// The emoji format
 pub enum EmojiFormat {
     /// Use the code mode, like ':smile:'
@@ -30,20 +34,65 @@ pub enum EmojiFormat {
     UseEmoji,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+/// How the scope is detected from the changed files, when no `scope_paths` is configured
+pub enum ScopeDiscovery {
+    /// No detection
+    Disabled,
+    /// Use the Cargo, npm, or pnpm workspace members
+    #[default]
+    Workspace,
+    /// Use the top-level directories
+    Directories,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
 /// The Gitmojis configuration
 pub struct GitmojiConfig {
+    /// Add the `--all` flag for git commit command
     auto_add: bool,
+    /// The commit specification
     specification: CommitSpecification,
+    /// How the emojis are written in the commits
     format: EmojiFormat,
+    /// Sign the commits
     signed: bool,
+    /// Prompt for the scope
     scope: bool,
+    /// The URL of the catalog, used without `sources`
     update_url: Url,
+    /// The SHA-256 digest pinning the catalog of the `update_url`, in hexadecimal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update_sha256: Option<String>,
+    /// The minisign public key verifying the catalog of the `update_url`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update_public_key: Option<String>,
+    /// The named sources of the catalog
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sources: Vec<CatalogSource>,
+    /// The last update of the catalog
     #[serde(with = "time::serde::iso8601::option")]
+    #[schemars(with = "Option<String>")]
     last_update: Option<OffsetDateTime>,
+    /// The gitmojis catalog
     gitmojis: Vec<Gitmoji>,
+    /// The conventional emoji commits catalog
     conventional_commit_emojis: Vec<ConventionalEmojiCommit>,
+    /// The rules restricting the gitmojis
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    rules: Vec<GitmojiRule>,
+    /// How the scope is detected from the changed files
+    scope_discovery: ScopeDiscovery,
+    /// The scopes associated with path glob patterns
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    scope_paths: Vec<ScopePaths>,
+    /// The allowed scopes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    scopes: Vec<AllowedScope>,
+    /// Allow a scope outside of the allowed scopes
+    allow_other_scope: bool,
 }
 
 impl GitmojiConfig {
@@ -64,29 +113,60 @@ impl GitmojiConfig {
             signed,
             scope,
             update_url,
+            update_sha256: None,
+            update_public_key: None,
+            sources: vec![],
             last_update: None,
             gitmojis: vec![],
             conventional_commit_emojis: vec![],
+            rules: vec![],
+            scope_discovery: ScopeDiscovery::Workspace,
+            scope_paths: vec![],
+            scopes: vec![],
+            allow_other_scope: false,
         }
     }
 
     /// Merge with a local configuration
+    ///
+    /// The merged configuration is only for the current repository, it is never written back
     pub fn merge(&mut self, local_config: &LocalGitmojiConfig) {
         if let Some(auto_add) = local_config.auto_add() {
             self.auto_add = auto_add;
         }
+        if let Some(specification) = local_config.specification() {
+            self.specification = specification;
+        }
         if let Some(format) = local_config.format() {
             self.format = format;
         }
         if let Some(signed) = local_config.signed() {
             self.signed = signed;
         }
+        if let Some(scope) = local_config.scope() {
+            self.scope = scope;
+        }
         if let Some(gitmojis) = local_config.gitmojis() {
             self.gitmojis = gitmojis.to_vec();
         }
         if let Some(conventional_commit_emoji) = local_config.conventional_commit_emojis() {
             self.conventional_commit_emojis = conventional_commit_emoji.to_vec();
         }
+        if let Some(rules) = local_config.rules() {
+            self.rules = rules.to_vec();
+        }
+        if let Some(scope_discovery) = local_config.scope_discovery() {
+            self.scope_discovery = scope_discovery;
+        }
+        if let Some(scope_paths) = local_config.scope_paths() {
+            self.scope_paths = scope_paths.to_vec();
+        }
+        if let Some(scopes) = local_config.scopes() {
+            self.scopes = scopes.to_vec();
+        }
+        if let Some(allow_other_scope) = local_config.allow_other_scope() {
+            self.allow_other_scope = allow_other_scope;
+        }
     }
 
     /// If the "--all" is added to commit command
@@ -130,6 +210,60 @@ impl GitmojiConfig {
         self.update_url = update_url;
     }
 
+    /// The SHA-256 digest pinning the catalog of the `update_url`
+    #[must_use]
+    pub fn update_sha256(&self) -> Option<&str> {
+        self.update_sha256.as_deref()
+    }
+
+    /// The minisign public key verifying the catalog of the `update_url`
+    #[must_use]
+    pub fn update_public_key(&self) -> Option<&str> {
+        self.update_public_key.as_deref()
+    }
+
+    /// The configured catalog sources
+    #[must_use]
+    pub fn sources(&self) -> &[CatalogSource] {
+        self.sources.as_ref()
+    }
+
+    /// Set the catalog sources
+    pub fn set_sources(&mut self, sources: Vec<CatalogSource>) {
+        self.sources = sources;
+    }
+
+    /// The sources used for update
+    ///
+    /// Without configured sources, the `update_url` provides the catalog of the specification,
+    /// pinned with the `update_sha256` and the `update_public_key`.
+    /// A default `update_url` is replaced by the default URL of the specification catalog.
+    #[must_use]
+    pub fn catalog_sources(&self) -> Vec<CatalogSource> {
+        if !self.sources.is_empty() {
+            return self.sources.clone();
+        }
+        let kind = CatalogKind::from(self.specification);
+        let is_default = [DEFAULT_URL, CONVENTIONAL_EMOJI_COMMITS_DEFAULT_URL]
+            .contains(&self.update_url.as_str());
+        let url = if is_default {
+            kind.default_url()
+                .parse()
+                .unwrap_or_else(|_| self.update_url.clone())
+        } else {
+            self.update_url.clone()
+        };
+        let mut source = CatalogSource::new(
+            String::from("default"),
+            kind,
+            0,
+            CatalogLocation::Url { url },
+        );
+        source.set_sha256(self.update_sha256.clone());
+        source.set_public_key(self.update_public_key.clone());
+        vec![source]
+    }
+
     /// The last time the gitmoji list was updated
     #[must_use]
     pub const fn last_update(&self) -> Option<OffsetDateTime> {
@@ -155,21 +289,115 @@ impl GitmojiConfig {
     }
 
     /// Set conventional commit emojis
+    ///
+    /// The types are kept in the order of the map
     pub fn set_conventional_commit_emojis(
         &mut self,
-        conventional_commit_emojis: HashMap<String, ConventionalEmojiCommit>,
+        conventional_commit_emojis: IndexMap<String, ConventionalEmojiCommit>,
     ) {
         self.last_update = Some(OffsetDateTime::now_utc());
         self.conventional_commit_emojis = conventional_commit_emojis
             .into_iter()
-            .map(|(r#type, value)| ConventionalEmojiCommit {
-                r#type: r#type,
-                code: value.code,
-                description: value.description,
-                emoji: value.emoji,
-            })
+            .map(|(r#type, value)| ConventionalEmojiCommit { r#type, ..value })
             .collect();
     }
+
+    /// The rules restricting the gitmojis
+    #[must_use]
+    pub fn rules(&self) -> &[GitmojiRule] {
+        self.rules.as_ref()
+    }
+
+    /// How the scope is detected from the changed files
+    #[must_use]
+    pub const fn scope_discovery(&self) -> ScopeDiscovery {
+        self.scope_discovery
+    }
+
+    /// The scopes associated with path glob patterns
+    #[must_use]
+    pub fn scope_paths(&self) -> &[ScopePaths] {
+        self.scope_paths.as_ref()
+    }
+
+    /// The allowed scopes, any scope is allowed when empty
+    #[must_use]
+    pub fn scopes(&self) -> &[AllowedScope] {
+        self.scopes.as_ref()
+    }
+
+    /// If a scope outside of the allowed scopes can be used
+    #[must_use]
+    pub const fn allow_other_scope(&self) -> bool {
+        self.allow_other_scope
+    }
+
+    /// If the scope is allowed, each scope of a `,` separated list should be allowed
+    #[must_use]
+    pub fn is_scope_allowed(&self, scope: &str) -> bool {
+        self.scopes.is_empty()
+            || self.allow_other_scope
+            || scope.split(SCOPE_SEPARATOR).all(|scope| {
+                self.scopes
+                    .iter()
+                    .any(|allowed| allowed.name() == scope.trim())
+            })
+    }
+
+    /// Keep only the gitmojis allowed by the rules applying to the branch and the changed paths
+    ///
+    /// When several rules apply, a gitmoji allowed by one of them is kept.
+    pub fn apply_rules(&mut self, branch: Option<&str>, paths: &[String]) {
+        let rules = self
+            .rules
+            .iter()
+            .filter(|rule| rule.applies(branch, paths))
+            .collect::<Vec<_>>();
+        if rules.is_empty() {
+            return;
+        }
+
+        self.gitmojis.retain(|gitmoji| {
+            rules
+                .iter()
+                .any(|rule| rule.allows(gitmoji.emoji(), gitmoji.code(), gitmoji.name()))
+        });
+        self.conventional_commit_emojis.retain(|emoji| {
+            rules
+                .iter()
+                .any(|rule| rule.allows(emoji.emoji(), emoji.code(), Some(emoji.r#type())))
+        });
+    }
+
+    /// Find the gitmoji (emoji or code) starting the commit subject
+    ///
+    /// Both the gitmojis and the conventional emoji commits are considered.
+    #[must_use]
+    pub fn find_gitmoji_prefix<'s>(&self, subject: &'s str) -> Option<&'s str> {
+        let gitmojis = self
+            .gitmojis
+            .iter()
+            .flat_map(|gitmoji| [gitmoji.emoji(), gitmoji.code()]);
+        let conventional_commit_emojis = self
+            .conventional_commit_emojis
+            .iter()
+            .flat_map(|emoji| [emoji.emoji(), emoji.code()]);
+
+        gitmojis
+            .chain(conventional_commit_emojis)
+            .filter_map(|candidate| matching_prefix(subject, candidate))
+            .max_by_key(|prefix| prefix.len())
+    }
+}
+
+// Emojis may be written with or without the variation selector
+fn matching_prefix<'s>(subject: &'s str, candidate: &str) -> Option<&'s str> {
+    let candidate = candidate.trim_end_matches('\u{fe0f}');
+    if candidate.is_empty() || !subject.starts_with(candidate) {
+        return None;
+    }
+    let rest = subject[candidate.len()..].trim_start_matches('\u{fe0f}');
+    Some(&subject[..subject.len() - rest.len()])
 }
 
 impl Default for GitmojiConfig {
@@ -181,23 +409,48 @@ impl Default for GitmojiConfig {
             signed: false,
             scope: false,
             update_url: DEFAULT_URL.parse().expect("It's a valid URL"),
+            update_sha256: None,
+            update_public_key: None,
+            sources: vec![],
             last_update: None,
             gitmojis: vec![],
             conventional_commit_emojis: vec![],
+            rules: vec![],
+            scope_discovery: ScopeDiscovery::Workspace,
+            scope_paths: vec![],
+            scopes: vec![],
+            allow_other_scope: false,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
 /// The local gitmoji configuration
 pub struct LocalGitmojiConfig {
+    /// Add the `--all` flag for git commit command
     auto_add: Option<bool>,
+    /// The commit specification
     specification: Option<CommitSpecification>,
+    /// How the emojis are written in the commits
     format: Option<EmojiFormat>,
+    /// Sign the commits
     signed: Option<bool>,
+    /// Prompt for the scope
     scope: Option<bool>,
+    /// The gitmojis, replacing the catalog
     gitmojis: Option<Vec<Gitmoji>>,
+    /// The conventional emoji commits, replacing the catalog
     conventional_commit_emojis: Option<Vec<ConventionalEmojiCommit>>,
+    /// The rules restricting the gitmojis
+    rules: Option<Vec<GitmojiRule>>,
+    /// How the scope is detected from the changed files
+    scope_discovery: Option<ScopeDiscovery>,
+    /// The scopes associated with path glob patterns
+    scope_paths: Option<Vec<ScopePaths>>,
+    /// The allowed scopes
+    scopes: Option<Vec<AllowedScope>>,
+    /// Allow a scope outside of the allowed scopes
+    allow_other_scope: Option<bool>,
 }
 
 impl LocalGitmojiConfig {
@@ -242,15 +495,400 @@ impl LocalGitmojiConfig {
     pub fn conventional_commit_emojis(&self) -> Option<&[ConventionalEmojiCommit]> {
         self.conventional_commit_emojis.as_deref()
     }
+
+    /// The rules restricting the gitmojis
+    #[must_use]
+    pub fn rules(&self) -> Option<&[GitmojiRule]> {
+        self.rules.as_deref()
+    }
+
+    /// How the scope is detected from the changed files
+    #[must_use]
+    pub fn scope_discovery(&self) -> Option<ScopeDiscovery> {
+        self.scope_discovery
+    }
+
+    /// The scopes associated with path glob patterns
+    #[must_use]
+    pub fn scope_paths(&self) -> Option<&[ScopePaths]> {
+        self.scope_paths.as_deref()
+    }
+
+    /// The allowed scopes
+    #[must_use]
+    pub fn scopes(&self) -> Option<&[AllowedScope]> {
+        self.scopes.as_deref()
+    }
+
+    /// If a scope outside of the allowed scopes can be used
+    #[must_use]
+    pub fn allow_other_scope(&self) -> Option<bool> {
+        self.allow_other_scope
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+/// A scope allowed for the commits
+pub struct AllowedScope {
+    /// The scope
+    name: String,
+    /// The description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl AllowedScope {
+    /// Create an allowed scope
+    #[must_use]
+    pub fn new(name: String, description: Option<String>) -> Self {
+        Self { name, description }
+    }
+
+    /// The scope
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// The description
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+impl Display for AllowedScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.description {
+            Some(description) => write!(f, "{} - {description}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+/// A scope, detected when a changed file matches one of the `paths` glob patterns
+pub struct ScopePaths {
+    /// The scope
+    scope: String,
+    /// The path glob patterns, a pattern ending with `/` matches the whole directory
+    paths: Vec<String>,
+}
+
+impl ScopePaths {
+    /// Create a scope with its paths
+    #[must_use]
+    pub fn new(scope: String, paths: Vec<String>) -> Self {
+        Self { scope, paths }
+    }
+
+    /// The scope
+    #[must_use]
+    pub fn scope(&self) -> &str {
+        self.scope.as_ref()
+    }
+
+    /// The path glob patterns, a pattern ending with `/` matches the whole directory
+    #[must_use]
+    pub fn paths(&self) -> &[String] {
+        self.paths.as_ref()
+    }
+
+    /// If the file matches one of the paths
+    #[must_use]
+    pub fn matches(&self, path: &str) -> bool {
+        self.paths.iter().any(|pattern| glob_match(pattern, path))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+/// The catalog provided by a source
+pub enum CatalogKind {
+    /// The gitmojis, like <https://gitmoji.dev/api/gitmojis>
+    Gitmojis,
+    /// The conventional emoji commits types
+    ConventionalEmojiCommits,
+}
+
+impl CatalogKind {
+    /// The URL of the catalog, used without sources nor custom `update_url`
+    #[must_use]
+    pub const fn default_url(self) -> &'static str {
+        match self {
+            Self::Gitmojis => DEFAULT_URL,
+            Self::ConventionalEmojiCommits => CONVENTIONAL_EMOJI_COMMITS_DEFAULT_URL,
+        }
+    }
+}
+
+impl From<CommitSpecification> for CatalogKind {
+    fn from(specification: CommitSpecification) -> Self {
+        match specification {
+            CommitSpecification::Default => Self::Gitmojis,
+            CommitSpecification::ConventionalEmojiCommits => Self::ConventionalEmojiCommits,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+/// Where the catalog JSON is read
+pub enum CatalogLocation {
+    /// A remote URL
+    Url {
+        /// The URL
+        url: Url,
+    },
+    /// A local file
+    Path {
+        /// The file path
+        path: PathBuf,
+    },
+    /// A file in a git repository
+    Git {
+        /// The repository, cloned with `git clone`
+        git: String,
+        /// The file path in the repository
+        file: String,
+        /// The branch or tag, the default branch when missing
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rev: Option<String>,
+    },
+}
+
+impl CatalogLocation {
+    /// The location of the minisign signature, with the `.minisig` extension
+    #[must_use]
+    pub fn signature(&self) -> Self {
+        match self {
+            Self::Url { url } => {
+                let mut url = url.clone();
+                url.set_path(&format!("{}.minisig", url.path()));
+                Self::Url { url }
+            }
+            Self::Path { path } => {
+                let mut path = path.clone().into_os_string();
+                path.push(".minisig");
+                Self::Path { path: path.into() }
+            }
+            Self::Git { git, file, rev } => Self::Git {
+                git: git.clone(),
+                file: format!("{file}.minisig"),
+                rev: rev.clone(),
+            },
+        }
+    }
+}
+
+impl Display for CatalogLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Url { url } => write!(f, "{url}"),
+            Self::Path { path } => write!(f, "{}", path.display()),
+            Self::Git { git, file, rev } => {
+                write!(f, "{git}:{file}")?;
+                if let Some(rev) = rev {
+                    write!(f, "@{rev}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+/// A named source of the catalog
+///
+/// The catalogs of all sources are merged, on conflict the source with the highest `priority` wins.
+pub struct CatalogSource {
+    /// The source name, used by `gitmoji update --source`
+    name: String,
+    /// The catalog provided by the source
+    kind: CatalogKind,
+    /// The priority, on conflict the highest wins
+    #[serde(default)]
+    priority: i32,
+    /// Where the catalog is read
+    #[serde(flatten)]
+    location: CatalogLocation,
+    /// The SHA-256 digest of the catalog, in hexadecimal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+    /// The minisign public key verifying the `.minisig` signature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+}
+
+impl CatalogSource {
+    /// Create a catalog source
+    #[must_use]
+    pub fn new(name: String, kind: CatalogKind, priority: i32, location: CatalogLocation) -> Self {
+        Self {
+            name,
+            kind,
+            priority,
+            location,
+            sha256: None,
+            public_key: None,
+        }
+    }
+
+    /// The name, used by `gitmoji update --source`
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// The kind of catalog
+    #[must_use]
+    pub const fn kind(&self) -> CatalogKind {
+        self.kind
+    }
+
+    /// The priority, the highest wins
+    #[must_use]
+    pub const fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// The location
+    #[must_use]
+    pub const fn location(&self) -> &CatalogLocation {
+        &self.location
+    }
+
+    /// The pinned SHA-256 digest of the catalog, as an hexadecimal string
+    #[must_use]
+    pub fn sha256(&self) -> Option<&str> {
+        self.sha256.as_deref()
+    }
+
+    /// Pin the SHA-256 digest of the catalog
+    pub fn set_sha256(&mut self, sha256: Option<String>) {
+        self.sha256 = sha256;
+    }
+
+    /// The minisign public key, the catalog is signed in the `.minisig` file next to it
+    #[must_use]
+    pub fn public_key(&self) -> Option<&str> {
+        self.public_key.as_deref()
+    }
+
+    /// Set the minisign public key
+    pub fn set_public_key(&mut self, public_key: Option<String>) {
+        self.public_key = public_key;
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+/// A rule restricting the gitmojis allowed for a commit
+///
+/// The rule applies when the current branch matches `branch` (if any),
+/// and when one of the changed files matches `paths` (if any).
+/// Then the commit should use one of the `gitmojis`, given by emoji, code, or name.
+pub struct GitmojiRule {
+    /// The branch glob pattern
+    branch: Option<String>,
+    /// The path glob patterns, a pattern ending with `/` matches the whole directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    paths: Vec<String>,
+    /// The allowed gitmojis, by emoji, code, or name
+    gitmojis: Vec<String>,
+}
+
+impl GitmojiRule {
+    /// Create a rule
+    #[must_use]
+    pub fn new(branch: Option<String>, paths: Vec<String>, gitmojis: Vec<String>) -> Self {
+        Self {
+            branch,
+            paths,
+            gitmojis,
+        }
+    }
+
+    /// The branch glob pattern
+    #[must_use]
+    pub fn branch(&self) -> Option<&str> {
+        self.branch.as_deref()
+    }
+
+    /// The path glob patterns, a pattern ending with `/` matches the whole directory
+    #[must_use]
+    pub fn paths(&self) -> &[String] {
+        self.paths.as_ref()
+    }
+
+    /// The allowed gitmojis
+    #[must_use]
+    pub fn gitmojis(&self) -> &[String] {
+        self.gitmojis.as_ref()
+    }
+
+    /// If the rule applies to the branch and the changed paths
+    #[must_use]
+    pub fn applies(&self, branch: Option<&str>, paths: &[String]) -> bool {
+        let branch_match = match (&self.branch, branch) {
+            (None, _) => true,
+            (Some(pattern), Some(branch)) => glob_match(pattern, branch),
+            (Some(_), None) => false,
+        };
+        let paths_match = self.paths.is_empty()
+            || paths
+                .iter()
+                .any(|path| self.paths.iter().any(|pattern| glob_match(pattern, path)));
+
+        branch_match && paths_match
+    }
+
+    /// If the gitmoji is allowed, by emoji, code, or name
+    #[must_use]
+    pub fn allows(&self, emoji: &str, code: &str, name: Option<&str>) -> bool {
+        self.gitmojis
+            .iter()
+            .any(|allowed| is_same_gitmoji(allowed, emoji, code, name))
+    }
+}
+
+/// If the gitmoji given by emoji, code, or name, is the same gitmoji
+pub(crate) fn is_same_gitmoji(gitmoji: &str, emoji: &str, code: &str, name: Option<&str>) -> bool {
+    gitmoji == code
+        || Some(gitmoji) == name
+        || gitmoji.trim_end_matches('\u{fe0f}') == emoji.trim_end_matches('\u{fe0f}')
+}
+
+pub(crate) fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern = if pattern.ends_with('/') {
+        format!("{pattern}**")
+    } else {
+        pattern.to_string()
+    };
+    match GlobBuilder::new(&pattern).literal_separator(true).build() {
+        Ok(glob) => glob.compile_matcher().is_match(value),
+        Err(err) => {
+            warn!("Invalid glob pattern {pattern:?} because {err}");
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 /// A Gitmoji
 pub struct Gitmoji {
+    /// The emoji, like '🎨'
     emoji: String,
+    /// The code, like ':art:'
     code: String,
+    /// The name, like 'art'
     name: Option<String>,
+    /// The description
     description: Option<String>,
+    /// The semantic version bump, like 'minor'
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    semver: Option<String>,
+    /// The name of the catalog source providing the gitmoji
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
 }
 
 impl Gitmoji {
@@ -267,6 +905,8 @@ impl Gitmoji {
             code,
             name,
             description,
+            semver: None,
+            source: None,
         }
     }
 
@@ -293,6 +933,28 @@ impl Gitmoji {
     pub fn description(&self) -> Option<&str> {
         self.description.as_deref()
     }
+
+    /// The semantic version bump, like `minor`
+    #[must_use]
+    pub fn semver(&self) -> Option<&str> {
+        self.semver.as_deref()
+    }
+
+    /// Set the semantic version bump
+    pub fn set_semver(&mut self, semver: Option<String>) {
+        self.semver = semver;
+    }
+
+    /// The name of the catalog source providing the gitmoji
+    #[must_use]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Set the name of the catalog source
+    pub fn set_source(&mut self, source: Option<String>) {
+        self.source = source;
+    }
 }
 
 impl Display for Gitmoji {
@@ -313,13 +975,20 @@ impl Display for Gitmoji {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 /// A Conventional Commit Emoji
 pub struct ConventionalEmojiCommit {
+    /// The emoji, like '✨'
     emoji: String,
+    /// The code, like ':sparkles:'
     code: String,
+    /// The conventional commit type, like 'feat'
     r#type: String,
+    /// The description
     description: Option<String>,
+    /// The name of the catalog source providing the type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
 }
 
 impl ConventionalEmojiCommit {
@@ -331,6 +1000,7 @@ impl ConventionalEmojiCommit {
             code,
             r#type,
             description,
+            source: None,
         }
     }
 
@@ -357,6 +1027,17 @@ impl ConventionalEmojiCommit {
     pub fn description(&self) -> Option<&str> {
         self.description.as_deref()
     }
+
+    /// The name of the catalog source providing the type
+    #[must_use]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Set the name of the catalog source
+    pub fn set_source(&mut self, source: Option<String>) {
+        self.source = source;
+    }
 }
 
 impl Display for ConventionalEmojiCommit {
@@ -390,6 +1071,8 @@ mod tests {
             code: String::from("rocket"),
             name: Some(String::from("Initialize")),
             description: Some(String::from("Bla bla")),
+            semver: Some(String::from("minor")),
+            source: Some(String::from("upstream")),
         };
 
         // Serialize
@@ -411,6 +1094,8 @@ mod tests {
             code: String::from("rocket"),
             name: Some(String::from("Initialize")),
             description: Some(String::from("Bla bla")),
+            semver: None,
+            source: None,
         });
 
         // Serialize
@@ -423,4 +1108,208 @@ mod tests {
 
         check!(result == config);
     }
+
+    #[test]
+    fn should_serde_catalog_sources() {
+        let toml = r#"
+specification = "ConventionalEmojiCommits"
+
+[[sources]]
+name = "upstream"
+kind = "Gitmojis"
+url = "https://gitmoji.dev/api/gitmojis"
+
+[[sources]]
+name = "team"
+kind = "ConventionalEmojiCommits"
+priority = 10
+git = "https://example.com/team/conventions.git"
+file = "types.json"
+sha256 = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+
+[[sources]]
+name = "local"
+kind = "Gitmojis"
+priority = 20
+path = "gitmojis.json"
+"#;
+
+        let result = toml_edit::de::from_str::<GitmojiConfig>(toml);
+
+        let_assert!(Ok(config) = result);
+        check!(config.sources().len() == 3);
+        let_assert!([upstream, team, local] = config.sources());
+        check!(upstream.priority() == 0);
+        let_assert!(CatalogLocation::Url { .. } = upstream.location());
+        check!(team.kind() == CatalogKind::ConventionalEmojiCommits);
+        let_assert!(CatalogLocation::Git { rev: None, .. } = team.location());
+        check!(team.sha256().is_some());
+        check!(team
+            .location()
+            .signature()
+            .to_string()
+            .ends_with("types.json.minisig"));
+        let_assert!(CatalogLocation::Path { .. } = local.location());
+
+        let toml = toml_edit::ser::to_string(&config);
+        let_assert!(Ok(toml) = toml);
+        let result = toml_edit::de::from_str::<GitmojiConfig>(&toml);
+        check!(result.ok() == Some(config));
+    }
+
+    #[test]
+    fn should_use_update_url_without_sources() {
+        let config = GitmojiConfig {
+            specification: CommitSpecification::ConventionalEmojiCommits,
+            ..GitmojiConfig::default()
+        };
+
+        let sources = config.catalog_sources();
+
+        let_assert!([source] = sources.as_slice());
+        check!(source.kind() == CatalogKind::ConventionalEmojiCommits);
+        check!(source.location().to_string() == CONVENTIONAL_EMOJI_COMMITS_DEFAULT_URL);
+    }
+
+    #[test]
+    fn should_use_custom_update_url_without_sources() {
+        let config = GitmojiConfig {
+            specification: CommitSpecification::ConventionalEmojiCommits,
+            update_url: "https://example.com/types.json".parse().unwrap(),
+            ..GitmojiConfig::default()
+        };
+
+        let sources = config.catalog_sources();
+
+        let_assert!([source] = sources.as_slice());
+        check!(source.location().to_string() == "https://example.com/types.json");
+    }
+
+    #[test]
+    fn should_pin_update_url() {
+        let config = GitmojiConfig {
+            update_sha256: Some(String::from("abc")),
+            update_public_key: Some(String::from("RWQ")),
+            ..GitmojiConfig::default()
+        };
+
+        let sources = config.catalog_sources();
+
+        let_assert!([source] = sources.as_slice());
+        check!(source.sha256() == Some("abc"));
+        check!(source.public_key() == Some("RWQ"));
+    }
+
+    #[test]
+    fn should_apply_branch_rule() {
+        let rule = GitmojiRule::new(
+            Some(String::from("release/*")),
+            vec![],
+            vec![String::from(":bookmark:")],
+        );
+
+        check!(rule.applies(Some("release/1.0"), &[]));
+        check!(!rule.applies(Some("release/1.0/fix"), &[]));
+        check!(!rule.applies(Some("main"), &[]));
+        check!(!rule.applies(None, &[]));
+    }
+
+    #[test]
+    fn should_apply_paths_rule() {
+        let rule = GitmojiRule::new(
+            None,
+            vec![String::from("docs/"), String::from("**/migrations/*.sql")],
+            vec![String::from(":memo:")],
+        );
+
+        check!(rule.applies(None, &[String::from("docs/guide/index.md")]));
+        check!(rule.applies(Some("main"), &[String::from("db/migrations/001.sql")]));
+        check!(!rule.applies(None, &[String::from("src/docs.rs")]));
+        check!(!rule.applies(None, &[]));
+    }
+
+    #[test]
+    fn should_filter_gitmojis_with_rules() {
+        let mut config = GitmojiConfig::default();
+        for (emoji, code, name) in [
+            ("🔖", ":bookmark:", "bookmark"),
+            ("🚑️", ":ambulance:", "ambulance"),
+            ("✨", ":sparkles:", "sparkles"),
+        ] {
+            config.gitmojis.push(Gitmoji::new(
+                String::from(emoji),
+                String::from(code),
+                Some(String::from(name)),
+                None,
+            ));
+        }
+        config.rules = vec![
+            GitmojiRule::new(
+                Some(String::from("release/*")),
+                vec![],
+                vec![String::from("🔖"), String::from("ambulance")],
+            ),
+            GitmojiRule::new(
+                None,
+                vec![String::from("docs/")],
+                vec![String::from(":sparkles:")],
+            ),
+        ];
+
+        let mut main = config.clone();
+        main.apply_rules(Some("main"), &[]);
+        check!(main.gitmojis().len() == 3);
+
+        config.apply_rules(Some("release/2.0"), &[]);
+        let codes = config
+            .gitmojis()
+            .iter()
+            .map(Gitmoji::code)
+            .collect::<Vec<_>>();
+        check!(codes == [":bookmark:", ":ambulance:"]);
+    }
+
+    #[test]
+    fn should_union_gitmojis_of_applying_rules() {
+        let mut config = GitmojiConfig::default();
+        for (emoji, code, name) in [
+            ("📝", ":memo:", "memo"),
+            ("🗃️", ":card_file_box:", "card_file_box"),
+            ("✨", ":sparkles:", "sparkles"),
+        ] {
+            config.gitmojis.push(Gitmoji::new(
+                String::from(emoji),
+                String::from(code),
+                Some(String::from(name)),
+                None,
+            ));
+        }
+        config.rules = vec![
+            GitmojiRule::new(
+                None,
+                vec![String::from("docs/")],
+                vec![String::from(":memo:")],
+            ),
+            GitmojiRule::new(
+                None,
+                vec![String::from("migrations/")],
+                vec![String::from(":card_file_box:")],
+            ),
+        ];
+
+        config.apply_rules(
+            None,
+            &[
+                String::from("docs/schema.md"),
+                String::from("migrations/001.sql"),
+            ],
+        );
+
+        let codes = config
+            .gitmojis()
+            .iter()
+            .map(Gitmoji::code)
+            .collect::<Vec<_>>();
+        check!(codes == [":memo:", ":card_file_box:"]);
+    }
 }
@@ -0,0 +1,274 @@
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::sync::Mutex;
+
+use console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Editor, FuzzySelect, Input, Select};
+
+use crate::{Error, Result};
+
+/// The interactive prompts used by the commands
+pub trait Prompter {
+    /// Select an item, returns its index
+    ///
+    /// # Errors
+    /// Fail if the prompt cannot be answered
+    fn select(&self, prompt: &str, items: &[String], default: usize) -> Result<usize>;
+
+    /// Select an item with a fuzzy search, returns its index
+    ///
+    /// # Errors
+    /// Fail if the prompt cannot be answered
+    fn fuzzy_select(&self, prompt: &str, items: &[String], default: usize) -> Result<usize>;
+
+    /// Enter a text
+    ///
+    /// # Errors
+    /// Fail if the prompt cannot be answered
+    fn input(&self, prompt: &str, default: Option<&str>, allow_empty: bool) -> Result<String>;
+
+    /// Answer yes or no
+    ///
+    /// # Errors
+    /// Fail if the prompt cannot be answered
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool>;
+
+    /// Edit a multi-line text, an input by default
+    ///
+    /// # Errors
+    /// Fail if the prompt cannot be answered
+    fn edit(&self, prompt: &str, default: Option<&str>) -> Result<String> {
+        self.input(prompt, default, true)
+    }
+
+    /// If the prompts can be answered, otherwise a template may be used instead
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+/// The prompts on a terminal, with dialoguer
+pub struct DialoguerPrompter<'t> {
+    term: &'t Term,
+    theme: ColorfulTheme,
+}
+
+impl<'t> DialoguerPrompter<'t> {
+    /// Create the prompter on the terminal
+    #[must_use]
+    pub fn new(term: &'t Term) -> Self {
+        let theme = ColorfulTheme::default();
+        Self { term, theme }
+    }
+}
+
+impl Prompter for DialoguerPrompter<'_> {
+    fn select(&self, prompt: &str, items: &[String], default: usize) -> Result<usize> {
+        let result = Select::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .interact_on(self.term)?;
+        Ok(result)
+    }
+
+    fn fuzzy_select(&self, prompt: &str, items: &[String], default: usize) -> Result<usize> {
+        let result = FuzzySelect::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .interact_on(self.term)?;
+        Ok(result)
+    }
+
+    fn input(&self, prompt: &str, default: Option<&str>, allow_empty: bool) -> Result<String> {
+        let mut input = Input::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .allow_empty(allow_empty);
+        if let Some(default) = default {
+            input = input.default(default.to_string());
+        }
+        let result = input.interact_text_on(self.term)?;
+        Ok(result)
+    }
+
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool> {
+        let result = Confirm::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .default(default)
+            .interact_on(self.term)?;
+        Ok(result)
+    }
+
+    fn edit(&self, prompt: &str, default: Option<&str>) -> Result<String> {
+        let default = default.unwrap_or_default();
+        let mut editor = Editor::new();
+        if let Some(executable) = git_editor() {
+            editor.executable(executable);
+        }
+        self.term.write_line(prompt)?;
+        // The text is kept when the editor is closed without saving
+        let result = editor.edit(default)?.unwrap_or_else(|| default.to_string());
+        Ok(result.trim().to_string())
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.term.is_term() && std::io::stdin().is_terminal()
+    }
+}
+
+// The editor of git, like `core.editor`, the prompt is synchronous
+fn git_editor() -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["var", "GIT_EDITOR"])
+        .output()
+        .ok()?;
+    let result = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !result.is_empty()).then_some(result)
+}
+
+/// A scripted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// Use the default value
+    Default,
+    /// Select the item at the index
+    Select(usize),
+    /// Enter the text, or select the first item containing the text
+    Text(String),
+    /// Answer yes or no
+    Confirm(bool),
+}
+
+/// The prompts answered by a script, for tests or for embedding
+#[derive(Debug, Default)]
+pub struct ScriptedPrompter {
+    answers: Mutex<VecDeque<Answer>>,
+}
+
+impl ScriptedPrompter {
+    /// Create the prompter with the answers, in order
+    #[must_use]
+    pub fn new(answers: impl IntoIterator<Item = Answer>) -> Self {
+        let answers = Mutex::new(answers.into_iter().collect());
+        Self { answers }
+    }
+
+    /// The answers not used yet
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.answers.lock().map_or(0, |answers| answers.len())
+    }
+
+    fn next(&self, prompt: &str) -> Result<Answer> {
+        self.answers
+            .lock()
+            .ok()
+            .and_then(|mut answers| answers.pop_front())
+            .ok_or_else(|| Error::PromptError(format!("no answer for '{prompt}'")))
+    }
+
+    fn select_item(&self, prompt: &str, items: &[String], default: usize) -> Result<usize> {
+        let idx = match self.next(prompt)? {
+            Answer::Default => default,
+            Answer::Select(idx) => idx,
+            Answer::Text(text) => items
+                .iter()
+                .position(|item| item.contains(&text))
+                .ok_or_else(|| Error::PromptError(format!("no item '{text}' for '{prompt}'")))?,
+            Answer::Confirm(_) => return Err(unexpected(prompt)),
+        };
+        if idx >= items.len() {
+            return Err(Error::PromptError(format!(
+                "no item at {idx} for '{prompt}'"
+            )));
+        }
+
+        Ok(idx)
+    }
+}
+
+impl Prompter for ScriptedPrompter {
+    fn select(&self, prompt: &str, items: &[String], default: usize) -> Result<usize> {
+        self.select_item(prompt, items, default)
+    }
+
+    fn fuzzy_select(&self, prompt: &str, items: &[String], default: usize) -> Result<usize> {
+        self.select_item(prompt, items, default)
+    }
+
+    fn input(&self, prompt: &str, default: Option<&str>, allow_empty: bool) -> Result<String> {
+        let result = match self.next(prompt)? {
+            Answer::Default => default.unwrap_or_default().to_string(),
+            Answer::Text(text) => text,
+            Answer::Select(_) | Answer::Confirm(_) => return Err(unexpected(prompt)),
+        };
+        if result.is_empty() && !allow_empty {
+            return Err(Error::PromptError(format!("empty answer for '{prompt}'")));
+        }
+
+        Ok(result)
+    }
+
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool> {
+        match self.next(prompt)? {
+            Answer::Default => Ok(default),
+            Answer::Confirm(value) => Ok(value),
+            Answer::Select(_) | Answer::Text(_) => Err(unexpected(prompt)),
+        }
+    }
+
+    fn edit(&self, prompt: &str, default: Option<&str>) -> Result<String> {
+        match self.next(prompt)? {
+            Answer::Default => Ok(default.unwrap_or_default().to_string()),
+            Answer::Text(text) => Ok(text),
+            Answer::Select(_) | Answer::Confirm(_) => Err(unexpected(prompt)),
+        }
+    }
+}
+
+fn unexpected(prompt: &str) -> Error {
+    Error::PromptError(format!("unexpected answer for '{prompt}'"))
+}
+
+#[cfg(test)]
+#[allow(clippy::ignored_unit_patterns)]
+mod tests {
+    use assert2::{check, let_assert};
+
+    use super::*;
+
+    #[test]
+    fn should_answer_scripted_prompts() {
+        let items = vec![String::from("🐛 bug"), String::from("✨ sparkles")];
+        let prompter = ScriptedPrompter::new([
+            Answer::Text(String::from("sparkles")),
+            Answer::Default,
+            Answer::Confirm(true),
+        ]);
+
+        check!(prompter.fuzzy_select("Pick", &items, 0).ok() == Some(1));
+        check!(prompter.input("Scope", Some("*"), false).ok() == Some(String::from("*")));
+        check!(prompter.confirm("Signed", false).ok() == Some(true));
+        check!(prompter.remaining() == 0);
+    }
+
+    #[test]
+    fn should_fail_without_answer() {
+        let prompter = ScriptedPrompter::default();
+
+        let result = prompter.confirm("Signed", false);
+
+        let_assert!(Err(Error::PromptError(_)) = result);
+    }
+
+    #[test]
+    fn should_fail_with_unexpected_answer() {
+        let prompter = ScriptedPrompter::new([Answer::Confirm(true)]);
+
+        let result = prompter.input("Title", None, false);
+
+        let_assert!(Err(Error::PromptError(_)) = result);
+    }
+}
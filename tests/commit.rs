@@ -1,4 +1,10 @@
-use gitmoji_rs::{write_config, Gitmoji, GitmojiConfig};
+use std::env;
+
+use assert2::{check, let_assert};
+use clap::Parser;
+use gitmoji_rs::{
+    run, write_config, Answer, Error, Gitmoji, GitmojiConfig, ScriptedPrompter, Settings,
+};
 use rexpect::session::spawn_command;
 use serial_test::serial;
 
@@ -38,3 +44,141 @@ async fn should_have_commit_command() -> Result<(), rexpect::error::Error> {
 
     Ok(())
 }
+
+async fn init_repository() -> GitRepository {
+    let mut config = GitmojiConfig::default();
+    config.set_gitmojis(vec![Gitmoji::new(
+        String::from("🎉"),
+        String::from(":tada:"),
+        Some(String::from("tada")),
+        Some(String::from("Begin a project.")),
+    )]);
+    write_config(&config).await.unwrap();
+
+    let git_repo = GitRepository::default();
+    git_repo.write("README.md", "# Hello");
+    git_repo.stage("README.md");
+
+    git_repo
+}
+
+fn has_commit(git_repo: &GitRepository) -> bool {
+    let output = git_repo.git(&["rev-parse", "--verify", "--quiet", "HEAD"]);
+    output.status.success()
+}
+
+async fn gitmoji_commit(git_repo: &GitRepository, args: &[&str]) -> gitmoji_rs::Result<()> {
+    let settings = Settings::parse_from(["gitmoji", "commit"].iter().chain(args));
+    let prompter = ScriptedPrompter::new([
+        Answer::Default,
+        Answer::Text(String::from("Initial commit")),
+        Answer::Default,
+    ]);
+
+    let current_dir = env::current_dir().unwrap();
+    env::set_current_dir(git_repo.path()).unwrap();
+    let result = run(settings, &prompter).await;
+    env::set_current_dir(current_dir).unwrap();
+
+    check!(prompter.remaining() == 0);
+    result
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_not_commit_with_dry_run() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let result = gitmoji_commit(&git_repo, &["--dry-run"]).await;
+
+    let_assert!(Ok(()) = result);
+    check!(!has_commit(&git_repo));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_not_commit_with_print() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let result = gitmoji_commit(&git_repo, &["--print"]).await;
+
+    let_assert!(Ok(()) = result);
+    check!(!has_commit(&git_repo));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_forward_git_arguments() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let result = gitmoji_commit(
+        &git_repo,
+        &["--", "--no-verify", "--author", "Jane <jane@example.com>"],
+    )
+    .await;
+
+    let_assert!(Ok(()) = result);
+    let output = git_repo.git(&["log", "--format=%an %s"]);
+    check!(String::from_utf8_lossy(&output.stdout) == "Jane :tada: Initial commit\n");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_reject_conflicting_git_arguments() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+    let settings = Settings::parse_from(["gitmoji", "commit", "--", "-m", "Plop"]);
+
+    let result = run(settings, &ScriptedPrompter::default()).await;
+
+    let_assert!(Err(Error::InvalidGitArguments(_)) = result);
+    check!(!has_commit(&git_repo));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_resume_draft_after_failure() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+    let draft = git_repo.path().join(".git").join("GITMOJI_DRAFT");
+
+    // The author is invalid, so git fails after the prompts
+    let result = gitmoji_commit(&git_repo, &["--", "--author", "Jane"]).await;
+
+    // The git error output goes to the terminal
+    let_assert!(Err(Error::FailToCommit { stderr, .. }) = result);
+    check!(stderr.is_empty());
+    check!(!has_commit(&git_repo));
+    check!(draft.exists());
+
+    let settings = Settings::parse_from(["gitmoji", "commit", "--resume"]);
+    let current_dir = env::current_dir().unwrap();
+    env::set_current_dir(git_repo.path()).unwrap();
+    let result = run(settings, &ScriptedPrompter::default()).await;
+    env::set_current_dir(current_dir).unwrap();
+
+    let_assert!(Ok(()) = result);
+    let output = git_repo.git(&["log", "--format=%s"]);
+    check!(String::from_utf8_lossy(&output.stdout) == ":tada: Initial commit\n");
+    check!(!draft.exists());
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_capture_git_error_output_for_json_report() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let result = gitmoji_commit(
+        &git_repo,
+        &["--error-format", "json", "--", "--author", "Jane"],
+    )
+    .await;
+
+    let_assert!(Err(Error::FailToCommit { stderr, .. }) = result);
+    check!(stderr.contains("Jane"), "{stderr}");
+    check!(!has_commit(&git_repo));
+}
@@ -1,9 +1,9 @@
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Output};
 use std::str::FromStr;
 
 use anyhow::Ok;
-use assert_fs::fixture::{FileTouch, PathChild};
+use assert_fs::fixture::{FileTouch, FileWriteStr, PathChild};
 use assert_fs::TempDir;
 
 #[derive(Debug, thiserror::Error)]
@@ -48,6 +48,31 @@ impl GitRepository {
                 self.root
             )
         }
+
+        for (key, value) in [
+            ("user.name", "Gitmoji"),
+            ("user.email", "gitmoji@example.com"),
+            ("commit.gpgsign", "false"),
+        ] {
+            self.git(&["config", key, value]);
+        }
+    }
+
+    pub fn hooks_path(&self) -> PathBuf {
+        self.root.join(".git").join("hooks")
+    }
+
+    pub fn write(&self, file: &str, contents: &str) {
+        let child = self.dir.child(file);
+        child.write_str(contents).expect("Expect to write file");
+    }
+
+    pub fn git(&self, args: &[&str]) -> Output {
+        Command::new("git")
+            .current_dir(&self.root)
+            .args(args)
+            .output()
+            .unwrap()
     }
 
     pub fn touch(&self, file: &str) {
@@ -117,15 +142,11 @@ impl FromStr for GitCommit {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split_ascii_whitespace();
-        let id = split
-            .next()
-            .ok_or_else(|| anyhow::Error::msg("Commit id not found"))?
-            .to_string();
-        let message = split
-            .next()
-            .ok_or_else(|| anyhow::Error::msg("Commit id not found"))?
-            .to_string();
+        let (id, message) = s
+            .split_once(' ')
+            .ok_or_else(|| anyhow::Error::msg("Commit id not found"))?;
+        let id = id.to_string();
+        let message = message.to_string();
         Ok(Self { id, message })
     }
 }
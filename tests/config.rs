@@ -0,0 +1,412 @@
+use std::env;
+
+use assert2::{check, let_assert};
+use assert_cmd::Command;
+use gitmoji_rs::{
+    get_catalog_file, get_config_file, read_config_or_fail, write_config, CommitSpecification,
+    Gitmoji, GitmojiConfig, EXIT_INVALID_ARGUMENTS, EXIT_INVALID_CONFIG,
+};
+use serial_test::serial;
+
+mod common;
+pub use self::common::*;
+
+fn config() -> GitmojiConfig {
+    let mut config = GitmojiConfig::default();
+    config.set_gitmojis(vec![Gitmoji::new(
+        String::from("🎨"),
+        String::from(":art:"),
+        Some(String::from("art")),
+        Some(String::from("Improve structure / format of the code.")),
+    )]);
+    config
+}
+
+// Outside of this repository, so its local configuration is not merged
+async fn read_config(dir: &assert_fs::TempDir) -> gitmoji_rs::Result<GitmojiConfig> {
+    let current_dir = env::current_dir().unwrap();
+    env::set_current_dir(dir.path()).unwrap();
+    let result = read_config_or_fail().await;
+    env::set_current_dir(current_dir).unwrap();
+    result
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_write_catalog_in_cache() {
+    let dir = home_isolation();
+
+    let config = config();
+    write_config(&config).await.unwrap();
+
+    let preferences = std::fs::read_to_string(get_config_file().await.unwrap()).unwrap();
+    check!(preferences.contains("update_url"));
+    check!(!preferences.contains("[[gitmojis]]"));
+    let catalog = std::fs::read_to_string(get_catalog_file().await.unwrap()).unwrap();
+    check!(catalog.contains(":art:"));
+    let_assert!(Ok(result) = read_config(&dir).await);
+    check!(result == config);
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_migrate_single_file_config() {
+    let dir = home_isolation();
+    let config = config();
+    let config_file = get_config_file().await.unwrap();
+    let legacy = toml_edit::ser::to_string_pretty(&config).unwrap();
+    std::fs::write(&config_file, &legacy).unwrap();
+    let catalog_file = get_catalog_file().await.unwrap();
+    check!(!catalog_file.exists());
+
+    let result = read_config(&dir).await;
+
+    let_assert!(Ok(result) = result);
+    check!(result == config);
+    check!(std::fs::read_to_string(&config_file).unwrap() == legacy);
+    check!(!catalog_file.exists());
+
+    Command::cargo_bin("gitmoji")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["config", "migrate"])
+        .assert()
+        .success();
+    let_assert!(Ok(result) = read_config(&dir).await);
+    check!(result == config);
+    let preferences = std::fs::read_to_string(&config_file).unwrap();
+    check!(!preferences.contains(":art:"));
+    check!(preferences.contains("version = 3"));
+    let catalog = std::fs::read_to_string(&catalog_file).unwrap();
+    check!(catalog.contains(":art:"));
+    let backup = std::fs::read_to_string(config_file.with_extension("toml.v2.bak")).unwrap();
+    check!(backup.contains(":art:"));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_report_migration() {
+    let dir = home_isolation();
+    let config_file = get_config_file().await.unwrap();
+    let legacy =
+        "# My preferences\nauto_add = true\n\n[[gitmojis]]\nemoji = \"🎨\"\ncode = \":art:\"\n";
+    std::fs::write(&config_file, legacy).unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    gitmoji(&["config", "get", "--global", "auto_add"])
+        .success()
+        .stdout("true\n");
+    gitmoji(&["config", "list", "--global"]).success();
+    check!(std::fs::read_to_string(&config_file).unwrap() == legacy);
+
+    let output = gitmoji(&["config", "migrate", "--dry-run"]).success();
+
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    check!(stdout.contains("from version 1 to 3"));
+    check!(stdout.contains("v1 → v2: set the specification to 'Default'"));
+    check!(stdout.contains("v2 → v3: move `gitmojis` to the catalog in the cache directory"));
+    check!(std::fs::read_to_string(&config_file).unwrap() == legacy);
+
+    let output = gitmoji(&["config", "migrate"]).success();
+
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    check!(stdout.contains("The previous configuration is saved in"));
+    let preferences = std::fs::read_to_string(&config_file).unwrap();
+    check!(preferences.starts_with("# My preferences\nauto_add = true\n"));
+    check!(preferences.contains("version = 3"));
+    let backup = std::fs::read_to_string(config_file.with_extension("toml.v1.bak")).unwrap();
+    check!(backup == legacy);
+    let_assert!(Ok(result) = read_config(&dir).await);
+    check!(result.auto_add());
+    check!(result.gitmojis().len() == 1);
+
+    gitmoji(&["config", "migrate"])
+        .success()
+        .stdout("✅ The configuration is up to date (version 3)\n");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_get_and_set_preferences() {
+    let dir = home_isolation();
+    write_config(&config()).await.unwrap();
+    let config_file = get_config_file().await.unwrap();
+    let preferences = std::fs::read_to_string(&config_file).unwrap();
+    let preferences = preferences.replace("signed = false", "signed = false # with GPG");
+    std::fs::write(&config_file, format!("# My preferences\n{preferences}")).unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    gitmoji(&["config", "set", "signed", "true"]).success();
+    gitmoji(&["config", "set", "format", "UseCode"]).success();
+
+    gitmoji(&["config", "get", "signed"])
+        .success()
+        .stdout("true\n");
+    gitmoji(&["config", "get", "format"])
+        .success()
+        .stdout("UseCode\n");
+    let preferences = std::fs::read_to_string(&config_file).unwrap();
+    check!(preferences.starts_with("# My preferences\n"));
+    check!(preferences.contains("signed = true # with GPG\n"));
+
+    gitmoji(&["config", "set", "--local", "signed", "false"]).success();
+    let local = std::fs::read_to_string(dir.path().join(".gitmojis.toml")).unwrap();
+    check!(local == "signed = false\n");
+    gitmoji(&["config", "get", "signed"])
+        .success()
+        .stdout("false\n");
+    gitmoji(&["config", "get", "--global", "signed"])
+        .success()
+        .stdout("true\n");
+    gitmoji(&["config", "list", "--local"])
+        .success()
+        .stdout("signed=false\n");
+
+    gitmoji(&["config", "unset", "--local", "signed"]).success();
+    gitmoji(&["config", "get", "signed"])
+        .success()
+        .stdout("true\n");
+    gitmoji(&["config", "unset", "format"]).success();
+    gitmoji(&["config", "get", "--global", "format"])
+        .success()
+        .stdout("");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_merge_local_specification_and_scope() {
+    let dir = home_isolation();
+    write_config(&config()).await.unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    gitmoji(&[
+        "config",
+        "set",
+        "--local",
+        "specification",
+        "ConventionalEmojiCommits",
+    ])
+    .success();
+    gitmoji(&["config", "set", "--local", "scope", "true"]).success();
+
+    let_assert!(Ok(config) = read_config(&dir).await);
+    check!(config.specification() == CommitSpecification::ConventionalEmojiCommits);
+    check!(config.scope());
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_reject_invalid_preferences() {
+    let dir = home_isolation();
+    write_config(&config()).await.unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    gitmoji(&["config", "set", "signed", "yes"]).code(EXIT_INVALID_CONFIG);
+    gitmoji(&["config", "set", "format", "Emoji"]).code(EXIT_INVALID_CONFIG);
+    gitmoji(&["config", "set", "gitmojis", "[]"]).code(EXIT_INVALID_ARGUMENTS);
+    gitmoji(&[
+        "config",
+        "set",
+        "--local",
+        "update_url",
+        "https://example.com",
+    ])
+    .code(EXIT_INVALID_ARGUMENTS);
+    gitmoji(&["config", "get", "signed"])
+        .success()
+        .stdout("false\n");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_edit_config() {
+    let dir = home_isolation();
+    write_config(&config()).await.unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.env(
+            "GIT_EDITOR",
+            "sed -i.orig 's/auto_add = false/auto_add = true/'",
+        );
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    gitmoji(&["config", "edit"]).success();
+
+    gitmoji(&["config", "get", "auto_add"])
+        .success()
+        .stdout("true\n");
+}
+
+#[test]
+#[serial]
+fn should_override_config_with_env() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let config_file = dir.path().join("config").join("gitmojis.toml");
+    let catalog_file = dir.path().join("cache").join("catalog.toml");
+    std::fs::create_dir_all(config_file.parent().unwrap()).unwrap();
+    std::fs::write(
+        &config_file,
+        "version = 3\nspecification = \"Default\"\nformat = \"UseEmoji\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(catalog_file.parent().unwrap()).unwrap();
+    std::fs::write(
+        &catalog_file,
+        "[[gitmojis]]\nemoji = \"🎨\"\ncode = \":art:\"\ndescription = \"Art\"\n",
+    )
+    .unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.env("GITMOJI_CONFIG", &config_file);
+        cmd.env("GITMOJI_CATALOG", &catalog_file);
+        cmd.env("GITMOJI_FORMAT", "UseCode");
+        cmd.env("GITMOJI_SIGNED", "true");
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    let output = gitmoji(&["list"]).success();
+    check!(String::from_utf8_lossy(&output.get_output().stdout).contains(":art:"));
+    gitmoji(&["config", "get", "format"])
+        .success()
+        .stdout("UseCode\n");
+    gitmoji(&["config", "get", "signed"])
+        .success()
+        .stdout("true\n");
+    gitmoji(&["config", "get", "--global", "format"])
+        .success()
+        .stdout("UseEmoji\n");
+
+    gitmoji(&["config", "set", "auto_add", "true"]).success();
+
+    let preferences = std::fs::read_to_string(&config_file).unwrap();
+    check!(preferences.contains("auto_add = true"));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_report_invalid_config() {
+    let dir = home_isolation();
+    let config_file = get_config_file().await.unwrap();
+    std::fs::write(
+        &config_file,
+        "version = 3\nspecification = \"Default\"\nformat = 'emoji'\n",
+    )
+    .unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    let output = gitmoji(&["list"]).code(EXIT_INVALID_CONFIG);
+
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr).to_string();
+    let expected = format!(
+        "Invalid configuration {}:3:10, unknown variant `emoji`, expected `UseCode` or `UseEmoji`",
+        config_file.display()
+    );
+    check!(stderr.contains(&expected), "{stderr}");
+
+    let output = gitmoji(&["--error-format", "json", "list"]).code(EXIT_INVALID_CONFIG);
+
+    let report: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    check!(report["kind"] == "invalid_config_file");
+    check!(report["line"] == 3);
+    check!(report["column"] == 10);
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_report_invalid_catalog() {
+    let dir = home_isolation();
+    write_config(&config()).await.unwrap();
+    let catalog_file = get_catalog_file().await.unwrap();
+    std::fs::write(
+        &catalog_file,
+        "[[gitmojis]]\nemoji = 42\ncode = \":art:\"\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("gitmoji")
+        .unwrap()
+        .current_dir(dir.path())
+        .arg("list")
+        .assert()
+        .code(EXIT_INVALID_CONFIG);
+
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr).to_string();
+    let expected = format!("Invalid configuration {}:2:9,", catalog_file.display());
+    check!(stderr.contains(&expected), "{stderr}");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_validate_config() {
+    let dir = home_isolation();
+    let mut config = config();
+    let mut gitmojis = config.gitmojis().to_vec();
+    gitmojis.push(gitmojis[0].clone());
+    config.set_gitmojis(gitmojis);
+    write_config(&config).await.unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    let output = gitmoji(&["config", "validate"]).code(EXIT_INVALID_CONFIG);
+
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    check!(stdout.contains("⚠️  duplicate gitmoji code ':art:'"));
+    let output = gitmoji(&["list"]).success();
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr).to_string();
+    check!(
+        stderr.contains("The configuration has an issue, duplicate gitmoji code ':art:'"),
+        "{stderr}"
+    );
+
+    config.set_gitmojis(config.gitmojis()[..1].to_vec());
+    write_config(&config).await.unwrap();
+    std::fs::write(dir.path().join(".gitmojis.toml"), "[[scopes]]\nname = 42\n").unwrap();
+
+    let output = gitmoji(&["config", "validate"]).code(EXIT_INVALID_CONFIG);
+
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    check!(
+        stdout.contains("❌ Invalid configuration ./.gitmojis.toml:2:8,"),
+        "{stdout}"
+    );
+
+    std::fs::remove_file(dir.path().join(".gitmojis.toml")).unwrap();
+    let output = gitmoji(&["config", "validate"]).success();
+
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    check!(stdout.ends_with("✅ The configuration is valid\n"));
+}
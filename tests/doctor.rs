@@ -0,0 +1,59 @@
+use assert2::check;
+use assert_cmd::Command;
+use gitmoji_rs::{write_config, Gitmoji, GitmojiConfig, EXIT_DOCTOR};
+use serial_test::serial;
+
+mod common;
+pub use self::common::*;
+
+fn gitmoji(git_repo: &GitRepository, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.current_dir(git_repo.path());
+    cmd.args(args);
+    cmd
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_pass_with_config() {
+    let _dir = home_isolation();
+    let git_repo = GitRepository::default();
+    let mut config = GitmojiConfig::default();
+    config.set_gitmojis(vec![Gitmoji::new(
+        String::from("🎨"),
+        String::from(":art:"),
+        None,
+        None,
+    )]);
+    write_config(&config).await.unwrap();
+
+    let output = gitmoji(&git_repo, &["doctor", "--offline"])
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    check!(output.status.success(), "{stdout}");
+    check!(stdout.contains("✅ git: git version"));
+    check!(stdout.contains("✅ repository: inside a git repository"));
+    check!(stdout.contains("✅ catalog: 1 emoji(s)"));
+    check!(stdout.contains("hook add"));
+    check!(!stdout.contains("network"));
+}
+
+#[test_log::test]
+#[serial]
+fn should_fail_without_catalog() {
+    let _dir = home_isolation();
+    let git_repo = GitRepository::default();
+    git_repo.git(&["config", "gitmoji.file", "missing.toml"]);
+
+    let output = gitmoji(&git_repo, &["doctor", "--offline"])
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    check!(output.status.code() == Some(EXIT_DOCTOR));
+    check!(stdout.contains("❌ catalog: the catalog is empty\n   💡 run `gitmoji update`"));
+    check!(stdout.contains("⚠️  config:"));
+    check!(stdout.contains("missing.toml from `gitmoji.file` not found"));
+}
@@ -0,0 +1,153 @@
+use std::env;
+
+use assert2::{check, let_assert};
+use clap::Parser;
+use gitmoji_rs::{run, write_config, Answer, Gitmoji, GitmojiConfig, ScriptedPrompter, Settings};
+use serial_test::serial;
+
+mod common;
+pub use self::common::*;
+
+async fn init_repository() -> GitRepository {
+    let mut config = GitmojiConfig::default();
+    config.set_gitmojis(vec![Gitmoji::new(
+        String::from("🎉"),
+        String::from(":tada:"),
+        Some(String::from("tada")),
+        Some(String::from("Begin a project.")),
+    )]);
+    write_config(&config).await.unwrap();
+
+    let git_repo = GitRepository::default();
+    for (file, subject) in [("README.md", ":tada: First"), ("LICENSE", ":tada: Second")] {
+        git_repo.write(file, "Hello");
+        git_repo.stage(file);
+        git_repo.git(&["commit", "--message", subject]);
+    }
+    git_repo.write("README.md", "# Hello");
+    git_repo.stage("README.md");
+
+    git_repo
+}
+
+fn subjects(git_repo: &GitRepository) -> String {
+    let output = git_repo.git(&["log", "--format=%s"]);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+async fn gitmoji_fixup(
+    git_repo: &GitRepository,
+    args: &[&str],
+    answers: Vec<Answer>,
+) -> gitmoji_rs::Result<()> {
+    let settings = Settings::parse_from(["gitmoji", "fixup"].iter().chain(args));
+    let prompter = ScriptedPrompter::new(answers);
+
+    let current_dir = env::current_dir().unwrap();
+    env::set_current_dir(git_repo.path()).unwrap();
+    let result = run(settings, &prompter).await;
+    env::set_current_dir(current_dir).unwrap();
+
+    check!(prompter.remaining() == 0);
+    result
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_create_fixup_commit() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let result = gitmoji_fixup(&git_repo, &[], vec![Answer::Text(String::from("First"))]).await;
+
+    let_assert!(Ok(()) = result);
+    check!(subjects(&git_repo) == "fixup! :tada: First\n:tada: Second\n:tada: First\n");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_create_squash_commit() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let result = gitmoji_fixup(
+        &git_repo,
+        &["--kind", "squash"],
+        vec![Answer::Select(0), Answer::Default],
+    )
+    .await;
+
+    let_assert!(Ok(()) = result);
+    check!(subjects(&git_repo).starts_with("squash! :tada: Second\n"));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_autosquash_fixup_commit() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let result = gitmoji_fixup(
+        &git_repo,
+        &["--autosquash"],
+        vec![Answer::Text(String::from("First"))],
+    )
+    .await;
+
+    let_assert!(Ok(()) = result);
+    check!(subjects(&git_repo) == ":tada: Second\n:tada: First\n");
+    let output = git_repo.git(&["show", "HEAD~1:README.md"]);
+    check!(String::from_utf8_lossy(&output.stdout) == "# Hello");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_not_autosquash_over_merge_commit() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+    git_repo.git(&["stash"]);
+    git_repo.git(&["checkout", "-b", "feat"]);
+    git_repo.write("CHANGELOG.md", "Hello");
+    git_repo.stage("CHANGELOG.md");
+    git_repo.git(&["commit", "--message", ":tada: Changelog"]);
+    git_repo.git(&["checkout", "-"]);
+    git_repo.git(&["merge", "--no-ff", "--no-edit", "feat"]);
+    git_repo.git(&["stash", "pop"]);
+    git_repo.stage("README.md");
+    let before = subjects(&git_repo);
+
+    let result = gitmoji_fixup(
+        &git_repo,
+        &["--autosquash"],
+        vec![Answer::Text(String::from("First"))],
+    )
+    .await;
+
+    let_assert!(Err(gitmoji_rs::Error::CannotRewriteCommit { .. }) = result);
+    check!(subjects(&git_repo) == before);
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_create_amend_commit_without_changes() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+    git_repo.git(&["stash"]);
+
+    let result = gitmoji_fixup(
+        &git_repo,
+        &["--kind", "amend"],
+        vec![
+            Answer::Text(String::from("First")),
+            Answer::Default,
+            Answer::Text(String::from("Better")),
+            Answer::Default,
+        ],
+    )
+    .await;
+
+    let_assert!(Ok(()) = result);
+    check!(subjects(&git_repo) == "amend! :tada: First\n:tada: Second\n:tada: First\n");
+    let output = git_repo.git(&["log", "-1", "--format=%b"]);
+    check!(String::from_utf8_lossy(&output.stdout).trim() == ":tada: Better");
+}
@@ -0,0 +1,185 @@
+use std::fs;
+
+use assert2::{check, let_assert};
+use assert_cmd::cargo::cargo_bin;
+use assert_cmd::Command;
+use gitmoji_rs::{write_config, Gitmoji, GitmojiConfig};
+use serial_test::serial;
+
+mod common;
+pub use self::common::*;
+
+async fn write_test_config() {
+    let mut config = GitmojiConfig::default();
+    config.set_gitmojis(vec![Gitmoji::new(
+        String::from("⚡️"),
+        String::from(":zap:"),
+        Some(String::from("zap")),
+        Some(String::from("Improve performance.")),
+    )]);
+    write_config(&config).await.unwrap();
+}
+
+fn gitmoji(git_repo: &GitRepository, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.current_dir(git_repo.path());
+    cmd.args(args);
+    cmd
+}
+
+#[test_log::test]
+#[serial]
+fn should_add_and_remove_hooks() {
+    let _dir = home_isolation();
+    let git_repo = GitRepository::default();
+
+    gitmoji(&git_repo, &["hook", "add"]).assert().success();
+
+    let hook = git_repo.hooks_path().join("prepare-commit-msg");
+    let_assert!(Ok(contents) = fs::read_to_string(&hook));
+    let binary = cargo_bin("gitmoji");
+    check!(contents.contains(&*binary.to_string_lossy()));
+    check!(git_repo.hooks_path().join("commit-msg").exists());
+
+    let output = gitmoji(&git_repo, &["hook", "status"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    check!(stdout.matches("is installed").count() == 2);
+
+    gitmoji(&git_repo, &["hook", "remove"]).assert().success();
+
+    check!(!hook.exists());
+    check!(!git_repo.hooks_path().join("commit-msg").exists());
+}
+
+#[test_log::test]
+#[serial]
+fn should_chain_existing_hook() {
+    let _dir = home_isolation();
+    let git_repo = GitRepository::default();
+    let existing = "#!/bin/sh\nexit 0\n";
+    git_repo.write(".git/hooks/commit-msg", existing);
+    let hook = git_repo.hooks_path().join("commit-msg");
+
+    gitmoji(&git_repo, &["hook", "add"]).assert();
+    let_assert!(Ok(contents) = fs::read_to_string(&hook));
+    check!(contents == existing);
+    check!(!git_repo.hooks_path().join("prepare-commit-msg").exists());
+
+    gitmoji(&git_repo, &["hook", "add", "--chain"])
+        .assert()
+        .success();
+    let_assert!(Ok(contents) = fs::read_to_string(&hook));
+    check!(contents.contains("commit-msg.pre-gitmoji"));
+    check!(git_repo
+        .hooks_path()
+        .join("commit-msg.pre-gitmoji")
+        .exists());
+
+    gitmoji(&git_repo, &["hook", "remove"]).assert().success();
+    let_assert!(Ok(contents) = fs::read_to_string(&hook));
+    check!(contents == existing);
+    check!(!git_repo
+        .hooks_path()
+        .join("commit-msg.pre-gitmoji")
+        .exists());
+}
+
+#[test_log::test]
+#[serial]
+fn should_keep_foreign_hook_on_remove() {
+    let _dir = home_isolation();
+    let git_repo = GitRepository::default();
+    gitmoji(&git_repo, &["hook", "add"]).assert().success();
+    let foreign = "#!/bin/sh\nexit 0\n";
+    git_repo.write(".git/hooks/commit-msg", foreign);
+
+    gitmoji(&git_repo, &["hook", "remove"]).assert().success();
+
+    let_assert!(Ok(contents) = fs::read_to_string(git_repo.hooks_path().join("commit-msg")));
+    check!(contents == foreign);
+    check!(!git_repo.hooks_path().join("prepare-commit-msg").exists());
+}
+
+#[test_log::test]
+#[serial]
+fn should_add_hooks_in_relative_hooks_path() {
+    let _dir = home_isolation();
+    let git_repo = GitRepository::default();
+    git_repo.git(&["config", "core.hooksPath", ".githooks"]);
+    git_repo.write("sub/file.txt", "");
+
+    let mut cmd = gitmoji(&git_repo, &["hook", "add"]);
+    cmd.current_dir(git_repo.path().join("sub"));
+    cmd.assert().success();
+
+    check!(git_repo.path().join(".githooks/commit-msg").exists());
+    check!(!git_repo.path().join("sub/.githooks").exists());
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_write_template_without_tty() {
+    let _dir = home_isolation();
+    write_test_config().await;
+    let git_repo = GitRepository::default();
+    git_repo.write("COMMIT_MSG", "# Please enter the commit message\n");
+
+    gitmoji(&git_repo, &["hook", "apply", "COMMIT_MSG"])
+        .assert()
+        .success();
+
+    let_assert!(Ok(contents) = fs::read_to_string(git_repo.path().join("COMMIT_MSG")));
+    check!(contents.starts_with("<gitmoji> <commit title>\n"));
+    check!(contents.contains("⚡️ :zap: zap - Improve performance."));
+    check!(contents.ends_with("# Please enter the commit message\n"));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_commit_through_hooks() {
+    let _dir = home_isolation();
+    write_test_config().await;
+    let git_repo = GitRepository::default();
+    gitmoji(&git_repo, &["hook", "add"]).assert().success();
+
+    git_repo.touch("fast.txt");
+    git_repo.stage("fast.txt");
+    let output = git_repo.git(&["commit", "-m", ":zap: Faster"]);
+    check!(output.status.success(), "{output:?}");
+    let commits = git_repo.list_commits(None);
+    let_assert!([commit] = commits.as_slice());
+    check!(commit.message() == ":zap: Faster");
+
+    git_repo.touch("slow.txt");
+    git_repo.stage("slow.txt");
+    let output = git_repo.git(&["-c", "core.editor=true", "commit"]);
+    check!(!output.status.success(), "{output:?}");
+    check!(git_repo.list_commits(None).len() == 1);
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_merge_through_hooks() {
+    let _dir = home_isolation();
+    write_test_config().await;
+    let git_repo = GitRepository::default();
+    gitmoji(&git_repo, &["hook", "add"]).assert().success();
+    git_repo.touch("fast.txt");
+    git_repo.stage("fast.txt");
+    let output = git_repo.git(&["commit", "-m", ":zap: Faster"]);
+    check!(output.status.success(), "{output:?}");
+
+    git_repo.git(&["checkout", "-b", "feat"]);
+    git_repo.touch("faster.txt");
+    git_repo.stage("faster.txt");
+    let output = git_repo.git(&["commit", "-m", ":zap: Even faster"]);
+    check!(output.status.success(), "{output:?}");
+    git_repo.git(&["checkout", "-"]);
+
+    let output = git_repo.git(&["-c", "core.editor=true", "merge", "--no-ff", "feat"]);
+
+    check!(output.status.success(), "{output:?}");
+    let commits = git_repo.list_commits(None);
+    check!(commits.len() == 3);
+    check!(commits[0].message().starts_with("Merge branch 'feat'"));
+}
@@ -1,142 +1,135 @@
-use assert2::check;
-use assert_cmd::Command;
-use gitmoji_rs::{read_config_or_fail, EmojiFormat, GitmojiConfig, DEFAULT_URL};
-use rexpect::session::spawn_command;
+use std::env;
+
+use assert2::{check, let_assert};
+use clap::Parser;
+use gitmoji_rs::{
+    create_config, read_config_or_fail, run, Answer, EmojiFormat, Error, GitmojiConfig,
+    ScriptedPrompter, Settings, DEFAULT_URL,
+};
 use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
 mod common;
 pub use self::common::*;
 
-#[test_log::test(tokio::test)]
-#[serial]
-#[ignore = "does not work on CI"]
-async fn should_have_init_command_default_values() -> Result<(), rexpect::error::Error> {
-    let _dir = home_isolation();
-
-    let mut cmd = cargo_bin_command("gitmoji");
-    cmd.arg("-v");
-    cmd.arg("init");
-
-    let mut p = spawn_command(cmd, Some(10_000))?;
-
-    // Auto git add
-    p.exp_regex("Enable automatic \"git add .\"")?;
-    p.send_line("")?;
-
-    // Format
-    p.exp_regex("Select how emojis should be used in commits")?;
-    p.send_line("")?;
-
-    // Signed
-    p.exp_regex("Enable signed commits")?;
-    p.send_line("")?;
-
-    // Scope
-    p.exp_regex("Enable scope prompt")?;
-    p.send_line("")?;
-
-    // URL
-    p.exp_regex("Set gitmojis api url")?;
-    p.send_line("")?;
-
-    p.exp_eof()?;
+const GITMOJIS: &str = r#"{
+    "gitmojis": [
+        {
+            "emoji": "🎨",
+            "entity": "&#x1f3a8;",
+            "code": ":art:",
+            "description": "Improve structure / format of the code.",
+            "name": "art",
+            "semver": null
+        }
+    ]
+}"#;
+
+async fn mock_server() -> MockServer {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/gitmoji"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(GITMOJIS, "application/json"))
+        .mount(&mock_server)
+        .await;
+    mock_server
+}
 
-    // Check config exists
-    let config = read_config_or_fail().await.unwrap();
-    check!(config.auto_add() == false);
-    check!(*config.format() == EmojiFormat::UseCode);
-    check!(config.signed() == false);
-    check!(config.scope() == false);
-    check!(config.update_url() == DEFAULT_URL);
+// Outside of this repository, so its local configuration is not merged
+async fn gitmoji_init(
+    dir: &assert_fs::TempDir,
+    args: &[&str],
+    answers: Vec<Answer>,
+) -> gitmoji_rs::Result<GitmojiConfig> {
+    let settings = Settings::parse_from(["gitmoji", "init"].iter().chain(args));
+    let prompter = ScriptedPrompter::new(answers);
+
+    let current_dir = env::current_dir().unwrap();
+    env::set_current_dir(dir.path()).unwrap();
+    let result = match run(settings, &prompter).await {
+        Ok(()) => read_config_or_fail().await,
+        Err(err) => Err(err),
+    };
+    env::set_current_dir(current_dir).unwrap();
 
-    Ok(())
+    check!(prompter.remaining() == 0);
+    result
 }
 
 #[test_log::test(tokio::test)]
 #[serial]
-#[ignore = "depends on working directory"]
-async fn should_have_init_command_default_flag() -> anyhow::Result<()> {
-    let _dir = home_isolation();
-    let mut cmd = Command::cargo_bin("gitmoji")?;
-    cmd.arg("init");
-    cmd.arg("--default");
+async fn should_have_init_command_default_values() {
+    let dir = home_isolation();
+    let mock_server = mock_server().await;
+    let url = format!("{}/gitmoji", mock_server.uri());
+    let mut answers = vec![Answer::Default; 5];
+    answers.push(Answer::Text(url.clone()));
 
-    cmd.assert().success();
+    let result = gitmoji_init(&dir, &[], answers).await;
 
-    // Check config exists
-    let config = read_config_or_fail().await?;
+    let_assert!(Ok(config) = result);
     check!(config.auto_add() == false);
     check!(*config.format() == EmojiFormat::UseCode);
     check!(config.signed() == false);
     check!(config.scope() == false);
-    check!(config.update_url() == DEFAULT_URL);
-
-    Ok(())
+    check!(config.update_url() == url);
+    check!(config.gitmojis().len() == 1);
 }
 
-async fn gitmoji_init(
-    git_add: bool,
-    format: EmojiFormat,
-    signed: bool,
-    scope: bool,
-    url: &str,
-) -> Result<GitmojiConfig, rexpect::error::Error> {
-    let _dir = home_isolation();
-
-    let mut cmd = cargo_bin_command("gitmoji");
-    cmd.arg("-v");
-    cmd.arg("init");
-
-    let mut p = spawn_command(cmd, Some(10_000))?;
-
-    // Auto git add
-    p.exp_regex("Enable automatic \"git add .\"")?;
-    let answer = if git_add { "y" } else { "n" };
-    p.send_line(answer)?;
-
-    // Format
-    p.exp_regex("Select how emojis should be used in commits")?;
-    let answer = match format {
-        EmojiFormat::UseCode => "",
-        EmojiFormat::UseEmoji => "\x1b[B", // "ESC[1B";
-    };
-    p.send_line(answer)?;
-
-    // Signed
-    p.exp_regex("Enable signed commits")?;
-    let answer = if signed { "y" } else { "n" };
-    p.send_line(answer)?;
-
-    // Scope
-    p.exp_regex("Enable scope prompt")?;
-    let answer = if scope { "y" } else { "n" };
-    p.send_line(answer)?;
-
-    // URL
-    p.exp_regex("Set gitmojis api url")?;
-    p.send_line(url)?;
+// Without a proxy answering, the catalog cannot be fetched, but the default URL is used
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_have_init_command_default_flag() {
+    let dir = home_isolation();
+    env::set_var("HTTPS_PROXY", "http://127.0.0.1:9");
 
-    p.exp_eof()?;
+    let result = gitmoji_init(&dir, &["--default"], vec![]).await;
 
-    let config = read_config_or_fail().await.unwrap();
-    Ok(config)
+    env::remove_var("HTTPS_PROXY");
+    let_assert!(Err(Error::CannotUpdateConfig { url, .. }) = result);
+    check!(url == DEFAULT_URL);
 }
 
 #[test_log::test(tokio::test)]
-#[ignore = "does not work"]
-async fn should_have_init_command_use_values() -> anyhow::Result<()> {
-    let url = "http://plop.org";
-
-    let config = gitmoji_init(true, EmojiFormat::UseEmoji, true, true, url)
-        .await
-        .unwrap();
-
-    // Check config exists
+#[serial]
+async fn should_have_init_command_use_values() {
+    let dir = home_isolation();
+    let mock_server = mock_server().await;
+    let url = format!("{}/gitmoji", mock_server.uri());
+
+    let result = gitmoji_init(
+        &dir,
+        &[],
+        vec![
+            Answer::Confirm(true),
+            Answer::Default,
+            Answer::Select(1),
+            Answer::Confirm(true),
+            Answer::Confirm(true),
+            Answer::Text(url.clone()),
+        ],
+    )
+    .await;
+
+    let_assert!(Ok(config) = result);
     check!(config.auto_add() == true);
     check!(*config.format() == EmojiFormat::UseEmoji);
     check!(config.signed() == true);
     check!(config.scope() == true);
     check!(config.update_url() == url);
+}
+
+#[test]
+fn should_create_config_with_default_answers() {
+    let prompter = ScriptedPrompter::new(vec![Answer::Default; 6]);
 
-    Ok(())
+    let config = create_config(&prompter).unwrap();
+
+    check!(config.auto_add() == false);
+    check!(*config.format() == EmojiFormat::UseCode);
+    check!(config.signed() == false);
+    check!(config.scope() == false);
+    check!(config.update_url() == DEFAULT_URL);
+    check!(prompter.remaining() == 0);
 }
@@ -0,0 +1,126 @@
+use std::env;
+
+use assert2::{check, let_assert};
+use clap::Parser;
+use gitmoji_rs::{
+    run, write_config, Answer, Error, Gitmoji, GitmojiConfig, ScriptedPrompter, Settings,
+};
+use serial_test::serial;
+
+mod common;
+pub use self::common::*;
+
+async fn init_repository() -> GitRepository {
+    let mut config = GitmojiConfig::default();
+    config.set_gitmojis(vec![
+        Gitmoji::new(
+            String::from("🎉"),
+            String::from(":tada:"),
+            Some(String::from("tada")),
+            Some(String::from("Begin a project.")),
+        ),
+        Gitmoji::new(
+            String::from("📝"),
+            String::from(":memo:"),
+            Some(String::from("memo")),
+            Some(String::from("Add or update documentation.")),
+        ),
+    ]);
+    write_config(&config).await.unwrap();
+
+    let git_repo = GitRepository::default();
+    for (file, message) in [
+        ("README.md", ":tada: First"),
+        ("LICENSE", ":tada: Add the license\n\nRefs: #42"),
+        ("CHANGELOG.md", ":tada: Third"),
+    ] {
+        git_repo.write(file, "Hello");
+        git_repo.stage(file);
+        git_repo.git(&["commit", "--message", message]);
+    }
+
+    git_repo
+}
+
+fn messages(git_repo: &GitRepository) -> String {
+    let output = git_repo.git(&["log", "--format=%B"]);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+async fn gitmoji_reword(
+    git_repo: &GitRepository,
+    rev: &str,
+    answers: Vec<Answer>,
+) -> gitmoji_rs::Result<()> {
+    let settings = Settings::parse_from(["gitmoji", "reword", rev]);
+    let prompter = ScriptedPrompter::new(answers);
+
+    let current_dir = env::current_dir().unwrap();
+    env::set_current_dir(git_repo.path()).unwrap();
+    let result = run(settings, &prompter).await;
+    env::set_current_dir(current_dir).unwrap();
+
+    check!(prompter.remaining() == 0);
+    result
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_reword_past_commit() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+    git_repo.write("README.md", "# Hello");
+    git_repo.stage("README.md");
+
+    let result = gitmoji_reword(
+        &git_repo,
+        "HEAD~1",
+        vec![
+            Answer::Text(String::from("memo")),
+            Answer::Default,
+            Answer::Default,
+        ],
+    )
+    .await;
+
+    let_assert!(Ok(()) = result);
+    check!(
+        messages(&git_repo)
+            == ":tada: Third\n\n:memo: Add the license\n\nRefs: #42\n\n:tada: First\n\n"
+    );
+    let output = git_repo.git(&["diff", "HEAD", "--name-only"]);
+    check!(String::from_utf8_lossy(&output.stdout) == "README.md\n");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_reword_head_commit() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let result = gitmoji_reword(
+        &git_repo,
+        "HEAD",
+        vec![
+            Answer::Default,
+            Answer::Text(String::from("Add the changelog")),
+            Answer::Default,
+        ],
+    )
+    .await;
+
+    let_assert!(Ok(()) = result);
+    check!(messages(&git_repo).starts_with(":tada: Add the changelog\n\n:tada: Add the license"));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_not_reword_published_commit() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+    git_repo.git(&["update-ref", "refs/remotes/origin/main", "HEAD~1"]);
+
+    let result = gitmoji_reword(&git_repo, "HEAD~2", vec![]).await;
+
+    let_assert!(Err(Error::CannotRewriteCommit { .. }) = result);
+}
@@ -0,0 +1,26 @@
+use std::path::Path;
+
+use assert2::check;
+use assert_cmd::Command;
+use rstest::rstest;
+
+mod common;
+pub use self::common::*;
+
+// The shipped schemas are updated with `gitmoji config schema > schemas/config.schema.json`
+#[rstest]
+#[case::global("--global", "config.schema.json")]
+#[case::local("--local", "local-config.schema.json")]
+#[test_log::test]
+fn should_ship_the_schema(#[case] target: &str, #[case] file: &str) {
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.args(["config", "schema", target]);
+
+    let output = cmd.assert().success();
+
+    let schema = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("schemas")
+        .join(file);
+    let expected = std::fs::read_to_string(schema).unwrap();
+    check!(String::from_utf8_lossy(&output.get_output().stdout) == expected);
+}
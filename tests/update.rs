@@ -1,5 +1,9 @@
 use assert_cmd::Command;
-use gitmoji_rs::{write_config, EmojiFormat, GitmojiConfig, EXIT_CANNOT_UPDATE};
+use gitmoji_rs::{
+    get_config_file, read_config_or_fail, write_config, CatalogKind, CatalogLocation,
+    CatalogSource, CommitSpecification, EmojiFormat, GitmojiConfig, ScopeDiscovery,
+    EXIT_CANNOT_UPDATE, EXIT_INVALID_ARGUMENTS, EXIT_UPDATE_AVAILABLE,
+};
 use serial_test::serial;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -16,6 +20,7 @@ async fn should_have_update_command() {
     let url = format!("{}/gitmoji", &mock_server.uri());
     let config = GitmojiConfig::new(
         false,
+        CommitSpecification::Default,
         EmojiFormat::UseCode,
         false,
         false,
@@ -59,6 +64,7 @@ async fn should_have_update_command_fail_bad_config() {
     let url = format!("{}/gitmoji", &mock_server.uri());
     let config = GitmojiConfig::new(
         false,
+        CommitSpecification::Default,
         EmojiFormat::UseCode,
         false,
         false,
@@ -91,3 +97,287 @@ async fn should_have_update_command_without_config() {
     let _ = dbg!(cmd.ok());
     cmd.assert().success();
 }
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_update_from_named_source() {
+    let dir = home_isolation();
+
+    let mock_server = MockServer::start().await;
+    let catalog = dir.path().join("gitmojis.json");
+    std::fs::write(
+        &catalog,
+        r#"{"gitmojis": [{"emoji": "🎨", "code": ":art:", "description": "Local", "name": "art"}]}"#,
+    )
+    .unwrap();
+    let mut config = GitmojiConfig::default();
+    config.set_sources(vec![
+        CatalogSource::new(
+            String::from("upstream"),
+            CatalogKind::Gitmojis,
+            0,
+            CatalogLocation::Url {
+                url: format!("{}/gitmoji", mock_server.uri()).parse().unwrap(),
+            },
+        ),
+        CatalogSource::new(
+            String::from("local"),
+            CatalogKind::Gitmojis,
+            10,
+            CatalogLocation::Path { path: catalog },
+        ),
+    ]);
+    write_config(&config).await.unwrap();
+
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.current_dir(dir.path());
+    cmd.args(["update", "--source", "local"]);
+
+    cmd.assert().success();
+    // Outside of this repository, so its local configuration is not merged
+    let current_dir = std::env::current_dir().unwrap();
+    std::env::set_current_dir(dir.path()).unwrap();
+    let config = read_config_or_fail().await;
+    std::env::set_current_dir(current_dir).unwrap();
+    let config = config.unwrap();
+    let descriptions = config
+        .gitmojis()
+        .iter()
+        .filter_map(|it| it.description())
+        .collect::<Vec<_>>();
+    assert2::check!(descriptions == ["Local"]);
+    assert2::check!(mock_server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .is_empty());
+
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.args(["update", "--source", "unknown"]);
+
+    cmd.assert().code(EXIT_INVALID_ARGUMENTS);
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_check_catalog_update() {
+    let dir = home_isolation();
+
+    let catalog = dir.path().join("gitmojis.json");
+    std::fs::write(
+        &catalog,
+        r#"{"gitmojis": [{"emoji": "🎨", "code": ":art:", "description": "Art", "name": "art"}]}"#,
+    )
+    .unwrap();
+    let mut config = GitmojiConfig::default();
+    config.set_sources(vec![CatalogSource::new(
+        String::from("local"),
+        CatalogKind::Gitmojis,
+        0,
+        CatalogLocation::Path { path: catalog },
+    )]);
+    write_config(&config).await.unwrap();
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(dir.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    gitmoji(&["update", "--check"])
+        .code(EXIT_UPDATE_AVAILABLE)
+        .stdout("➕ 🎨 :art:\n");
+    gitmoji(&["update"]).success();
+    gitmoji(&["update", "--check"])
+        .success()
+        .stdout("✅ The catalog is up to date\n");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_verify_pinned_update_url() {
+    let _dir = home_isolation();
+
+    let mock_server = MockServer::start().await;
+    let url = format!("{}/gitmoji", &mock_server.uri());
+    let config = GitmojiConfig::new(
+        false,
+        CommitSpecification::Default,
+        EmojiFormat::UseCode,
+        false,
+        false,
+        url.parse().unwrap(),
+    );
+    write_config(&config).await.unwrap();
+    Mock::given(method("GET"))
+        .and(path("/gitmoji"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(r#"{"gitmojis": []}"#, "application/json"),
+        )
+        .mount(&mock_server)
+        .await;
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    gitmoji(&["config", "set", "update_sha256", &"0".repeat(64)]).success();
+    let output = gitmoji(&["update"]).code(EXIT_CANNOT_UPDATE);
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr).to_string();
+    assert2::check!(stderr.contains("Cannot verify the catalog of the source 'default'"));
+
+    // The SHA-256 digest of `{"gitmojis": []}`
+    let sha256 = "6434d355147bf5e8609c1a2a124270356434a175936216d8a76cb0bfdc2836e8";
+    gitmoji(&["config", "set", "update_sha256", sha256]).success();
+    gitmoji(&["update"]).success();
+    let config = read_config_or_fail().await.unwrap();
+    assert2::check!(config.update_sha256() == Some(sha256));
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_not_save_env_overrides_on_update() {
+    let _dir = home_isolation();
+
+    let mock_server = MockServer::start().await;
+    let url = format!("{}/gitmoji", &mock_server.uri());
+    let config = GitmojiConfig::new(
+        false,
+        CommitSpecification::Default,
+        EmojiFormat::UseCode,
+        false,
+        false,
+        url.parse().unwrap(),
+    );
+    write_config(&config).await.unwrap();
+    Mock::given(method("GET"))
+        .and(path("/gitmoji"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(r#"{"gitmojis": []}"#, "application/json"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.env("GITMOJI_SIGNED", "true");
+    cmd.arg("update");
+
+    cmd.assert().success();
+    let config = read_config_or_fail().await.unwrap();
+    assert2::check!(!config.signed());
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_keep_preferences_comments_on_update() {
+    let _dir = home_isolation();
+
+    let mock_server = MockServer::start().await;
+    let url = format!("{}/gitmoji", &mock_server.uri());
+    write_config(&GitmojiConfig::default()).await.unwrap();
+    let config_file = get_config_file().await.unwrap();
+    let contents = std::fs::read_to_string(&config_file).unwrap();
+    std::fs::write(&config_file, format!("# keep me\n{contents}")).unwrap();
+    Mock::given(method("GET"))
+        .and(path("/gitmoji"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(r#"{"gitmojis": []}"#, "application/json"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.args(["update", &url]);
+
+    cmd.assert().success();
+    let contents = std::fs::read_to_string(&config_file).unwrap();
+    assert2::check!(contents.starts_with("# keep me\n"), "{contents}");
+    let config = read_config_or_fail().await.unwrap();
+    assert2::check!(config.update_url() == url);
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_not_save_local_config_on_update() {
+    let _dir = home_isolation();
+    let git_repo = GitRepository::default();
+    git_repo.write(
+        ".gitmojis.toml",
+        "format = \"UseEmoji\"\n\n[[rules]]\npaths = [\"docs/\"]\ngitmojis = [\":memo:\"]\n",
+    );
+
+    let mock_server = MockServer::start().await;
+    let url = format!("{}/gitmoji", &mock_server.uri());
+    Mock::given(method("GET"))
+        .and(path("/gitmoji"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(r#"{"gitmojis": []}"#, "application/json"),
+        )
+        .mount(&mock_server)
+        .await;
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(git_repo.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    // Without, then with a user config file
+    gitmoji(&["update", &url]).success();
+    gitmoji(&["update"]).success();
+
+    let contents = std::fs::read_to_string(get_config_file().await.unwrap()).unwrap();
+    assert2::check!(!contents.contains("rules"), "{contents}");
+    assert2::check!(!contents.contains("UseEmoji"), "{contents}");
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_not_save_local_scopes_on_update() {
+    let dir = home_isolation();
+    let git_repo = GitRepository::default();
+    git_repo.write(
+        ".gitmojis.toml",
+        r#"scope_discovery = "Directories"
+allow_other_scope = true
+
+[[scope_paths]]
+scope = "docs"
+paths = ["docs/"]
+
+[[scopes]]
+name = "api"
+"#,
+    );
+
+    let mock_server = MockServer::start().await;
+    let url = format!("{}/gitmoji", &mock_server.uri());
+    Mock::given(method("GET"))
+        .and(path("/gitmoji"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(r#"{"gitmojis": []}"#, "application/json"),
+        )
+        .mount(&mock_server)
+        .await;
+    let gitmoji = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+        cmd.current_dir(git_repo.path());
+        cmd.args(args);
+        cmd.assert()
+    };
+
+    gitmoji(&["update", &url]).success();
+    gitmoji(&["update"]).success();
+
+    // Outside of the repository, the user config has the default scope settings
+    let current_dir = std::env::current_dir().unwrap();
+    std::env::set_current_dir(dir.path()).unwrap();
+    let config = read_config_or_fail().await;
+    std::env::set_current_dir(current_dir).unwrap();
+    let config = config.unwrap();
+    assert2::check!(config.scope_paths().is_empty());
+    assert2::check!(config.scopes().is_empty());
+    assert2::check!(!config.allow_other_scope());
+    assert2::check!(config.scope_discovery() == ScopeDiscovery::Workspace);
+}
@@ -0,0 +1,88 @@
+use assert2::check;
+use assert_cmd::Command;
+use gitmoji_rs::{write_config, Gitmoji, GitmojiConfig, EXIT_INVALID_COMMIT_MESSAGE};
+use serial_test::serial;
+
+mod common;
+pub use self::common::*;
+
+async fn init_repository() -> GitRepository {
+    let mut config = GitmojiConfig::default();
+    config.set_gitmojis(vec![
+        Gitmoji::new(
+            String::from("📝"),
+            String::from(":memo:"),
+            Some(String::from("memo")),
+            None,
+        ),
+        Gitmoji::new(
+            String::from("⚡️"),
+            String::from(":zap:"),
+            Some(String::from("zap")),
+            None,
+        ),
+    ]);
+    write_config(&config).await.unwrap();
+
+    let git_repo = GitRepository::default();
+    git_repo.write(
+        ".gitmojis.toml",
+        r#"
+[[rules]]
+paths = ["docs/"]
+gitmojis = [":memo:"]
+"#,
+    );
+    git_repo.write("docs/index.md", "# Doc");
+    git_repo.stage("docs/index.md");
+    git_repo.git(&["commit", "-m", ":memo: Add documentation"]);
+
+    git_repo
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_validate_commits() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.current_dir(git_repo.path());
+    cmd.args(["validate", "HEAD"]);
+
+    cmd.assert().success();
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_reject_commits_not_following_rules() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+    git_repo.write("docs/index.md", "# Documentation");
+    git_repo.stage("docs/index.md");
+    git_repo.git(&["commit", "--no-verify", "-m", ":zap: Faster documentation"]);
+
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.current_dir(git_repo.path());
+    cmd.args(["validate", "HEAD"]);
+
+    cmd.assert().code(EXIT_INVALID_COMMIT_MESSAGE);
+}
+
+#[test_log::test(tokio::test)]
+#[serial]
+async fn should_report_json_error() {
+    let _dir = home_isolation();
+    let git_repo = init_repository().await;
+    git_repo.git(&["commit", "--allow-empty", "-m", "No gitmoji"]);
+
+    let mut cmd = Command::cargo_bin("gitmoji").unwrap();
+    cmd.current_dir(git_repo.path());
+    cmd.args(["--error-format", "json", "validate", "HEAD~1..HEAD"]);
+
+    let output = cmd.output().unwrap();
+    check!(output.status.code() == Some(EXIT_INVALID_COMMIT_MESSAGE));
+    let report: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    check!(report["kind"] == "invalid_commit_message");
+    check!(report["code"] == EXIT_INVALID_COMMIT_MESSAGE);
+}